@@ -0,0 +1,82 @@
+use criterion::Criterion;
+use criterion::{criterion_group, criterion_main};
+use oars::constructors::Bose;
+use oars::oa::{normalize_par, normalize_with_rng, OAConstructor};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+// The serial benchmarks use a fixed seed, so every run shuffles and jitters the same way and
+// the timing isn't confounded by `thread_rng`
+
+fn bench_normalize_small(c: &mut Criterion) {
+    let oa = Bose {
+        prime_base: 3,
+        dimensions: 3,
+    }
+    .gen()
+    .unwrap();
+    let mut rng = StdRng::seed_from_u64(0);
+    c.bench_function("normalize Bose (base 3, dims 3)", move |b| {
+        b.iter(|| normalize_with_rng::<u32, f64>(&oa, 1.0, true, &mut rng).unwrap())
+    });
+}
+
+fn bench_normalize_large(c: &mut Criterion) {
+    let oa = Bose {
+        prime_base: 53,
+        dimensions: 50,
+    }
+    .gen()
+    .unwrap();
+    let mut rng = StdRng::seed_from_u64(0);
+    c.bench_function("normalize Bose (base 53, dims 50)", move |b| {
+        b.iter(|| normalize_with_rng::<u32, f64>(&oa, 1.0, true, &mut rng).unwrap())
+    });
+}
+
+fn bench_normalize_large_par(c: &mut Criterion) {
+    let oa = Bose {
+        prime_base: 53,
+        dimensions: 50,
+    }
+    .gen()
+    .unwrap();
+    c.bench_function("normalize (parallel) Bose (base 53, dims 50)", move |b| {
+        b.iter(|| normalize_par::<u32, f64>(&oa, 1.0, true).unwrap())
+    });
+}
+
+fn bench_normalize_xlarge(c: &mut Criterion) {
+    let oa = Bose {
+        prime_base: 251,
+        dimensions: 250,
+    }
+    .gen()
+    .unwrap();
+    let mut rng = StdRng::seed_from_u64(0);
+    c.bench_function("normalize Bose (base 251, dims 250)", move |b| {
+        b.iter(|| normalize_with_rng::<u32, f64>(&oa, 1.0, true, &mut rng).unwrap())
+    });
+}
+
+fn bench_normalize_xlarge_par(c: &mut Criterion) {
+    let oa = Bose {
+        prime_base: 251,
+        dimensions: 250,
+    }
+    .gen()
+    .unwrap();
+    c.bench_function("normalize (parallel) Bose (base 251, dims 250)", move |b| {
+        b.iter(|| normalize_par::<u32, f64>(&oa, 1.0, true).unwrap())
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_normalize_small,
+    bench_normalize_large,
+    bench_normalize_large_par,
+    bench_normalize_xlarge,
+    bench_normalize_xlarge_par
+);
+criterion_main!(benches);
@@ -0,0 +1,127 @@
+//! Generate an orthogonal array from the command line and print it as CSV.
+//!
+//! ```text
+//! cargo run --example generate -- --method bush --base 3 --dims 4 --strength 3 --seed 42
+//! ```
+//!
+//! The array is constructed with the parameter checked constructors and normalized to the unit
+//! hypercube with a deterministic RNG, so the same arguments always produce the same points. Any
+//! error is printed to stderr and the process exits with the error's code.
+
+use oars::constructors::{BoseChecked, BushChecked};
+use oars::oa::normalize_with_config;
+use oars::prelude::*;
+use std::env;
+use std::io::{self, Write};
+use std::process::ExitCode;
+use std::str::FromStr;
+
+const USAGE: &str = "usage: generate [--method bose|bush] [--base P] [--dims D] [--strength T] \
+                     [--jitter J] [--seed S]";
+
+/// The options that can be supplied on the command line
+struct Args {
+    method: String,
+    base: u32,
+    dims: u32,
+    strength: u32,
+    jitter: f64,
+    seed: u64,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Self {
+            method: "bose".to_owned(),
+            base: 3,
+            dims: 3,
+            strength: 2,
+            jitter: 0.0,
+            seed: 0,
+        }
+    }
+}
+
+/// Parse the value of a flag, returning an error that names the flag if it is malformed
+fn parse_value<V: FromStr>(flag: &str, value: Option<String>) -> OarsResult<V> {
+    let value =
+        value.ok_or_else(|| OarsError::InvalidParams(format!("`{}` requires a value", flag)))?;
+    value
+        .parse()
+        .map_err(|_| OarsError::InvalidParams(format!("Invalid value for `{}`: {}", flag, value)))
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> OarsResult<Args> {
+    let mut parsed = Args::default();
+
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "--method" => parsed.method = parse_value(&flag, args.next())?,
+            "--base" => parsed.base = parse_value(&flag, args.next())?,
+            "--dims" => parsed.dims = parse_value(&flag, args.next())?,
+            "--strength" => parsed.strength = parse_value(&flag, args.next())?,
+            "--jitter" => parsed.jitter = parse_value(&flag, args.next())?,
+            "--seed" => parsed.seed = parse_value(&flag, args.next())?,
+            _ => {
+                return Err(OarsError::InvalidParams(format!(
+                    "Unknown argument `{}`\n{}",
+                    flag, USAGE
+                )))
+            }
+        }
+    }
+    Ok(parsed)
+}
+
+fn generate(args: &Args) -> OarsResult<OA<u32>> {
+    match args.method.as_str() {
+        "bose" => {
+            if args.strength != 2 {
+                return Err(OarsError::InvalidParams(
+                    "The Bose construction only supports a strength of 2".to_owned(),
+                ));
+            }
+            BoseChecked {
+                prime_base: args.base,
+                dimensions: args.dims,
+            }
+            .verify()?
+            .gen()
+        }
+        "bush" => BushChecked {
+            prime_base: args.base,
+            strength: args.strength,
+            dimensions: args.dims,
+        }
+        .verify()?
+        .gen(),
+        method => Err(OarsError::InvalidParams(format!(
+            "Unknown construction method `{}`",
+            method
+        ))),
+    }
+}
+
+fn run() -> OarsResult<()> {
+    let args = parse_args(env::args().skip(1))?;
+    let oa = generate(&args)?;
+    let points = normalize_with_config(&oa, args.jitter, true, &RngConfig::seeded(args.seed))?;
+
+    let mut out = io::stdout().lock();
+
+    for row in points.rows() {
+        let line: Vec<String> = row.iter().map(|x| x.to_string()).collect();
+        writeln!(out, "{}", line.join(","))?;
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(e.code() as u8)
+        }
+    }
+}
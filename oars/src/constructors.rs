@@ -48,3 +48,603 @@ pub use bose::Bose;
 pub use bose::BoseChecked;
 pub use bush::Bush;
 pub use bush::BushChecked;
+
+use crate::oa::{OAConstructor, OAResult, OA};
+use crate::utils::{format_integer, Integer, OarsError, OarsResult};
+use primes::is_prime;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Arc;
+
+#[cfg(feature = "serialize")]
+use serde_derive::{Deserialize, Serialize};
+
+#[cfg(feature = "serialize")]
+use std::io::Read;
+
+/// Describe a construction and the parameters of the orthogonal array it produces, in the form
+/// `name (params) -> OA(runs, factors, levels, strength, index)`.
+///
+/// This is shared by the `OAConstructor::describe` implementations of the constructors. The number
+/// of runs is `None` if it overflows, in which case it is formatted as `?`.
+fn describe_construction<T: Integer>(
+    name: &str,
+    params: &[(&str, T)],
+    runs: Option<T>,
+    factors: T,
+    levels: T,
+    strength: T,
+    index: T,
+) -> String {
+    let params: Vec<String> = params
+        .iter()
+        .map(|&(param, value)| format!("{}={}", param, format_integer(value)))
+        .collect();
+    format!(
+        "{} ({}) -> OA({}, {}, {}, {}, {})",
+        name,
+        params.join(", "),
+        runs.map_or_else(|| "?".to_owned(), format_integer),
+        format_integer(factors),
+        format_integer(levels),
+        format_integer(strength),
+        format_integer(index),
+    )
+}
+
+/// Get the names of the constructors that can produce an orthogonal array with `levels` levels.
+///
+/// This is useful for tooling that wants to suggest a construction method for some desired
+/// number of levels. Both the Bose and Bush constructions require the number of levels to be a
+/// prime number, so this returns an empty vector if `levels` is not prime.
+///
+/// ```
+/// use oars::constructors::supported_methods_for_levels;
+///
+/// assert!(supported_methods_for_levels(5) == vec!["Bose", "Bush"]);
+/// assert!(supported_methods_for_levels(4).is_empty());
+/// ```
+pub fn supported_methods_for_levels<T: Integer>(levels: T) -> Vec<&'static str> {
+    match levels.to_u64() {
+        Some(levels) if is_prime(levels) => vec!["Bose", "Bush"],
+        _ => Vec::new(),
+    }
+}
+
+/// Generate a Bose orthogonal array for each number of dimensions in a range.
+///
+/// This is a convenience method to precompute a family of designs with the same base. The
+/// parameters for each array are checked, so the result for a number of dimensions that is not
+/// valid for `prime_base` will be an error.
+///
+/// ```
+/// use oars::constructors::generate_series;
+///
+/// let series = generate_series(5, 2..5);
+/// assert!(series.len() == 3);
+/// assert!(series.iter().all(|oa| oa.is_ok()));
+/// ```
+pub fn generate_series<T: Integer>(prime_base: T, dims: Range<T>) -> Vec<OAResult<T>> {
+    num::range(dims.start, dims.end)
+        .map(|dimensions| {
+            BoseChecked {
+                prime_base,
+                dimensions,
+            }
+            .verify()
+            .and_then(|bose| bose.gen())
+        })
+        .collect()
+}
+
+/// The parameters for a construction method, independent of the integer type of the array.
+///
+/// This describes a construction as plain data, which is useful for batches of constructions
+/// and for looking up constructions by their parameters.
+///
+/// With the `serialize` feature, the parameters are (de)serialized with the name of the method in
+/// a `method` field, such as `{"method": "bose", "prime_base": 3, "dimensions": 3}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(Serialize, Deserialize),
+    serde(tag = "method", rename_all = "lowercase")
+)]
+pub enum ConstructionParams {
+    /// The parameters for the Bose construction
+    Bose {
+        /// The prime base of the array
+        prime_base: u64,
+
+        /// The number of dimensions of the array
+        dimensions: u64,
+    },
+
+    /// The parameters for the Bush construction
+    Bush {
+        /// The prime base of the array
+        prime_base: u64,
+
+        /// The strength of the array
+        strength: u64,
+
+        /// The number of dimensions of the array
+        dimensions: u64,
+    },
+}
+
+impl ConstructionParams {
+    /// Check the parameters with the checked variant of the constructor, then generate the
+    /// orthogonal array.
+    ///
+    /// This returns an error if the parameters are invalid, or if they don't fit in `T`.
+    pub fn generate<T: Integer>(&self) -> OAResult<T> {
+        let convert = |x: u64| {
+            T::from(x).ok_or_else(|| {
+                OarsError::InvalidParams(format!(
+                    "{} does not fit in the range of the target integer type",
+                    x
+                ))
+            })
+        };
+
+        match *self {
+            ConstructionParams::Bose {
+                prime_base,
+                dimensions,
+            } => BoseChecked {
+                prime_base: convert(prime_base)?,
+                dimensions: convert(dimensions)?,
+            }
+            .verify()?
+            .gen(),
+            ConstructionParams::Bush {
+                prime_base,
+                strength,
+                dimensions,
+            } => BushChecked {
+                prime_base: convert(prime_base)?,
+                strength: convert(strength)?,
+                dimensions: convert(dimensions)?,
+            }
+            .verify()?
+            .gen(),
+        }
+    }
+}
+
+/// Generate an orthogonal array for every set of parameters, failing if any of the constructions
+/// fail.
+///
+/// Unlike `generate_series`, this returns a single result, and it stops at the first construction
+/// that fails. This is meant for pipelines that need the complete family of arrays.
+pub fn generate_all_or_fail<T: Integer>(params: &[ConstructionParams]) -> OarsResult<Vec<OA<T>>> {
+    params.iter().map(ConstructionParams::generate).collect()
+}
+
+/// Read an experiment spec in JSON, and generate the orthogonal array that it describes.
+///
+/// The spec is the serialized form of `ConstructionParams`, such as
+/// `{"method": "bush", "prime_base": 5, "strength": 3, "dimensions": 4}`. The parameters are
+/// checked with the checked variant of the constructor for the method. This returns an error if
+/// the spec can't be parsed, or if the parameters are invalid.
+///
+/// ```
+/// use oars::constructors::from_spec_json;
+/// use oars::oa::OA;
+///
+/// let spec = r#"{"method": "bose", "prime_base": 3, "dimensions": 3}"#;
+/// let oa: OA<u32> = from_spec_json(spec.as_bytes()).unwrap();
+/// assert!(oa.points.dim() == (9, 3));
+/// ```
+#[cfg(feature = "serialize")]
+pub fn from_spec_json<R: Read, T: Integer>(reader: R) -> OAResult<T> {
+    let params: ConstructionParams = serde_json::from_reader(reader)
+        .map_err(|e| OarsError::InvalidParams(format!("Could not parse the spec: {}", e)))?;
+    params.generate()
+}
+
+/// Suggest the constructions that provide `desired_factors` factors within a budget of `max_runs`
+/// runs.
+///
+/// This enumerates every prime base that the Bose and Bush constructions can use to provide the
+/// desired number of factors, along with every strength of at least 2 for the Bush construction,
+/// where the resulting array has at most `max_runs` runs. The suggestions are ordered by base, and
+/// then by the number of runs. This returns an empty vector if no construction fits the budget,
+/// or if fewer than 2 factors are desired.
+///
+/// ```
+/// use oars::constructors::{suggest_params, ConstructionParams};
+///
+/// let suggestions = suggest_params(25, 4);
+/// assert!(suggestions.contains(&ConstructionParams::Bose {
+///     prime_base: 5,
+///     dimensions: 4,
+/// }));
+/// ```
+pub fn suggest_params(max_runs: u64, desired_factors: u64) -> Vec<ConstructionParams> {
+    let mut suggestions = Vec::new();
+
+    if desired_factors < 2 {
+        return suggestions;
+    }
+
+    // Every construction has at least `prime_base^2` runs, which bounds the bases to try
+    for prime_base in (2..).take_while(|&p: &u64| p.checked_mul(p).is_some_and(|n| n <= max_runs)) {
+        if !is_prime(prime_base) || Bose::max_dimensions_for_runs(prime_base) < desired_factors {
+            continue;
+        }
+        suggestions.push(ConstructionParams::Bose {
+            prime_base,
+            dimensions: desired_factors,
+        });
+
+        for strength in 2..=prime_base {
+            match prime_base.checked_pow(strength as u32) {
+                Some(runs) if runs <= max_runs => suggestions.push(ConstructionParams::Bush {
+                    prime_base,
+                    strength,
+                    dimensions: desired_factors,
+                }),
+                _ => break,
+            }
+        }
+    }
+    suggestions
+}
+
+/// A collection of heterogeneous orthogonal array constructors.
+///
+/// This is meant for tools that let users assemble a list of constructions, possibly with
+/// different construction methods, and generate all of them at once.
+///
+/// ```
+/// use oars::constructors::{Bose, Bush, ConstructorSet};
+///
+/// let mut set = ConstructorSet::new();
+/// set.push(Box::new(Bose {
+///     prime_base: 3,
+///     dimensions: 3,
+/// }));
+/// set.push(Box::new(Bush {
+///     prime_base: 3,
+///     strength: 3,
+///     dimensions: 3,
+/// }));
+/// assert!(set.generate_all().iter().all(|oa| oa.is_ok()));
+/// ```
+#[derive(Default)]
+pub struct ConstructorSet<T: Integer> {
+    /// The constructors in the set, in the order they were added
+    constructors: Vec<Box<dyn OAConstructor<T>>>,
+}
+
+impl<T: Integer> ConstructorSet<T> {
+    /// Create an empty set of constructors
+    pub fn new() -> Self {
+        Self {
+            constructors: Vec::new(),
+        }
+    }
+
+    /// Add a constructor to the end of the set
+    pub fn push(&mut self, constructor: Box<dyn OAConstructor<T>>) {
+        self.constructors.push(constructor);
+    }
+
+    /// Get the number of constructors in the set
+    pub fn len(&self) -> usize {
+        self.constructors.len()
+    }
+
+    /// Check whether the set has no constructors
+    pub fn is_empty(&self) -> bool {
+        self.constructors.is_empty()
+    }
+
+    /// Generate an orthogonal array with every constructor in the set, in the order the
+    /// constructors were added.
+    pub fn generate_all(&self) -> Vec<OAResult<T>> {
+        self.constructors.iter().map(|c| c.gen()).collect()
+    }
+}
+
+impl<T: Integer> From<Vec<Box<dyn OAConstructor<T>>>> for ConstructorSet<T> {
+    fn from(constructors: Vec<Box<dyn OAConstructor<T>>>) -> Self {
+        Self { constructors }
+    }
+}
+
+/// A memoizing wrapper around the checked constructors, which caches every generated orthogonal
+/// array by its parameters.
+///
+/// Generating an array with parameters that were already requested returns the cached array
+/// instead of constructing it again, which helps interactive tools that keep regenerating the
+/// same design. Errors are not cached.
+///
+/// ```
+/// use oars::constructors::{CachedConstructor, ConstructionParams};
+///
+/// let mut cache = CachedConstructor::<u32>::new();
+/// let params = ConstructionParams::Bose {
+///     prime_base: 3,
+///     dimensions: 3,
+/// };
+/// let first = cache.get(&params).unwrap();
+/// let second = cache.get(&params).unwrap();
+/// assert!(first == second);
+/// assert!(cache.hits() == 1);
+/// ```
+pub struct CachedConstructor<T: Integer> {
+    /// The arrays that have been generated so far, keyed by their parameters
+    cache: HashMap<ConstructionParams, Arc<OA<T>>>,
+
+    /// The number of requests that were served from the cache
+    hits: usize,
+}
+
+impl<T: Integer> CachedConstructor<T> {
+    /// Create a cache with no arrays in it
+    pub fn new() -> Self {
+        Self {
+            cache: HashMap::new(),
+            hits: 0,
+        }
+    }
+
+    /// Get the orthogonal array for some parameters, generating it if it isn't in the cache.
+    ///
+    /// The parameters are checked before the array is generated, so this returns an error if the
+    /// parameters are invalid.
+    pub fn get(&mut self, params: &ConstructionParams) -> OarsResult<Arc<OA<T>>> {
+        if let Some(oa) = self.cache.get(params) {
+            self.hits += 1;
+            return Ok(Arc::clone(oa));
+        }
+        let oa = Arc::new(params.generate()?);
+        self.cache.insert(*params, Arc::clone(&oa));
+        Ok(oa)
+    }
+
+    /// Get the number of requests that were served from the cache
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Get the number of arrays in the cache
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Check whether the cache has no arrays
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Remove every array from the cache, and reset the number of hits
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.hits = 0;
+    }
+}
+
+impl<T: Integer> Default for CachedConstructor<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oa::{compute_index, verify};
+
+    #[test]
+    fn test_supported_methods_prime() {
+        assert!(supported_methods_for_levels(2) == vec!["Bose", "Bush"]);
+        assert!(supported_methods_for_levels(13_u8) == vec!["Bose", "Bush"]);
+    }
+
+    #[test]
+    fn test_supported_methods_non_prime() {
+        let methods = supported_methods_for_levels(4);
+        assert!(!methods.contains(&"Bose"));
+        assert!(!methods.contains(&"Bush"));
+        assert!(supported_methods_for_levels(1).is_empty());
+        assert!(supported_methods_for_levels(-3).is_empty());
+    }
+
+    #[test]
+    fn test_generate_series() {
+        let series = generate_series(5, 2..5);
+        assert!(series.len() == 3);
+
+        for (oa, dimensions) in series.into_iter().zip(2..5) {
+            let oa = oa.unwrap();
+            assert!(oa.factors == dimensions);
+            assert!(verify(&oa).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_generate_series_invalid_dims() {
+        let series = generate_series(3, 4..6);
+        assert!(series[0].is_ok());
+        assert!(series[1].is_err());
+    }
+
+    #[test]
+    fn test_constructor_set() {
+        let mut set = ConstructorSet::new();
+        assert!(set.is_empty());
+
+        set.push(Box::new(Bose {
+            prime_base: 3,
+            dimensions: 3,
+        }));
+        set.push(Box::new(Bush {
+            prime_base: 5,
+            strength: 3,
+            dimensions: 4,
+        }));
+        assert!(set.len() == 2);
+
+        let arrays: Vec<_> = set.generate_all().into_iter().map(Result::unwrap).collect();
+        assert!(arrays[0].points.dim() == (9, 3));
+        assert!(arrays[1].points.dim() == (125, 4));
+        assert!(arrays.iter().all(|oa| verify(oa).unwrap()));
+    }
+
+    #[test]
+    fn test_generate_all_or_fail() {
+        let params = vec![
+            ConstructionParams::Bose {
+                prime_base: 3,
+                dimensions: 3,
+            },
+            ConstructionParams::Bush {
+                prime_base: 5,
+                strength: 3,
+                dimensions: 4,
+            },
+        ];
+        let arrays: Vec<OA<u32>> = generate_all_or_fail(&params).unwrap();
+        assert!(arrays.len() == 2);
+        assert!(arrays[0].points.dim() == (9, 3));
+        assert!(arrays[1].points.dim() == (125, 4));
+
+        let mut params = params;
+        params.insert(
+            1,
+            ConstructionParams::Bose {
+                prime_base: 4,
+                dimensions: 3,
+            },
+        );
+        assert!(generate_all_or_fail::<u32>(&params).is_err());
+
+        // The parameters have to fit in the integer type of the array
+        let params = [ConstructionParams::Bose {
+            prime_base: 257,
+            dimensions: 3,
+        }];
+        assert!(generate_all_or_fail::<u8>(&params).is_err());
+    }
+
+    #[test]
+    fn test_constructors_store_computed_index() {
+        let constructors: Vec<Box<dyn OAConstructor<u32>>> = vec![
+            Box::new(Bose {
+                prime_base: 5,
+                dimensions: 4,
+            }),
+            Box::new(Bush {
+                prime_base: 3,
+                strength: 3,
+                dimensions: 4,
+            }),
+        ];
+
+        for constructor in constructors {
+            let oa = constructor.gen().unwrap();
+            let index = compute_index(oa.points.nrows(), oa.levels, oa.strength).unwrap();
+            assert!(oa.index == index);
+        }
+        assert!(compute_index(18, 3, 2).unwrap() == 2);
+        assert!(compute_index(10, 3, 2).is_err());
+    }
+
+    #[test]
+    fn test_suggest_params() {
+        let suggestions = suggest_params(25, 6);
+        assert!(suggestions.contains(&ConstructionParams::Bose {
+            prime_base: 5,
+            dimensions: 6,
+        }));
+        assert!(!suggestions.contains(&ConstructionParams::Bose {
+            prime_base: 5,
+            dimensions: 7,
+        }));
+        assert!(suggest_params(25, 7).is_empty());
+        assert!(suggest_params(24, 6).is_empty());
+
+        // Every suggestion fits the budget and can be generated
+        for params in suggest_params(200, 4) {
+            let oa: OA<u32> = params.generate().unwrap();
+            assert!(oa.points.nrows() <= 200);
+            assert!(oa.points.ncols() == 4);
+        }
+        assert!(suggest_params(200, 4).contains(&ConstructionParams::Bush {
+            prime_base: 5,
+            strength: 3,
+            dimensions: 4,
+        }));
+    }
+
+    #[test]
+    #[cfg(feature = "serialize")]
+    fn test_from_spec_json() {
+        let spec = r#"{"method":"bush","prime_base":5,"strength":3,"dimensions":4}"#;
+        let params: ConstructionParams = serde_json::from_str(spec).unwrap();
+        assert!(
+            params
+                == ConstructionParams::Bush {
+                    prime_base: 5,
+                    strength: 3,
+                    dimensions: 4,
+                }
+        );
+        let round_trip: ConstructionParams =
+            serde_json::from_str(&serde_json::to_string(&params).unwrap()).unwrap();
+        assert!(round_trip == params);
+
+        let oa: OA<u32> = from_spec_json(spec.as_bytes()).unwrap();
+        assert!(oa.points.dim() == (125, 4));
+        assert!(oa.strength == 3);
+        assert!(verify(&oa).unwrap());
+
+        let spec = r#"{"method":"bose","prime_base":4,"dimensions":3}"#;
+        assert!(from_spec_json::<_, u32>(spec.as_bytes()).is_err());
+
+        let spec = r#"{"method":"addelman","prime_base":5,"dimensions":3}"#;
+        assert!(from_spec_json::<_, u32>(spec.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_cached_constructor() {
+        let mut cache = CachedConstructor::<u32>::default();
+        let params = ConstructionParams::Bush {
+            prime_base: 5,
+            strength: 3,
+            dimensions: 4,
+        };
+        let first = cache.get(&params).unwrap();
+        assert!(cache.hits() == 0);
+
+        let second = cache.get(&params).unwrap();
+        assert!(first == second);
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(cache.hits() == 1);
+        assert!(cache.len() == 1);
+
+        let other = ConstructionParams::Bose {
+            prime_base: 5,
+            dimensions: 4,
+        };
+        assert!(cache.get(&other).unwrap().points.dim() == (25, 4));
+        assert!(cache.hits() == 1);
+        assert!(cache.len() == 2);
+
+        // Errors are not cached
+        let invalid = ConstructionParams::Bose {
+            prime_base: 4,
+            dimensions: 4,
+        };
+        assert!(cache.get(&invalid).is_err());
+        assert!(cache.len() == 2);
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert!(cache.hits() == 0);
+    }
+}
@@ -1,9 +1,11 @@
-use crate::oa::{OAConstructor, OAResult, OA};
-use crate::utils::{Integer, OarsError, OarsResult};
-use ndarray::Array2;
-use num::pow;
+use super::describe_construction;
+use crate::oa::{compute_index, ensure_valid, OAConstructor, OAResult, OA};
+use crate::perm_vec::PermutationVector;
+use crate::utils::{as_usize, checked_pow, from_usize_checked, Integer, OarsError, OarsResult};
+use ndarray::{Array2, Axis};
 use oars_proc_macro::Checked;
 use primes::is_prime;
+use rand::Rng;
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
@@ -12,7 +14,7 @@ use rayon::prelude::*;
 use crate::oa::ParOAConstructor;
 
 #[cfg(feature = "parallel")]
-use ndarray::{concatenate, Axis};
+use ndarray::concatenate;
 
 impl<T: Integer> BoseChecked<T> {
     /// Check the parameters for Bose construction
@@ -38,20 +40,56 @@ impl<T: Integer> BoseChecked<T> {
     /// # }
     /// ```
     pub fn verify(self) -> OarsResult<Bose<T>> {
+        let base = self
+            .prime_base
+            .to_u64()
+            .ok_or_else(|| OarsError::InvalidParams("Base out of range".into()))?;
+
+        if !is_prime(base) {
+            return Err(OarsError::InvalidParams("Base is not prime".into()));
+        }
+        self.assume_prime()
+    }
+
+    /// Check every parameter for Bose construction except for the primality of the base.
+    ///
+    /// This is meant for generating many arrays with a base that is already known to be prime,
+    /// where checking the primality of the base every time is redundant. The caller is
+    /// responsible for making sure that `prime_base` is prime: if it isn't, the resulting
+    /// orthogonal array will be invalid, just like using `Bose` directly.
+    pub fn assume_prime(self) -> OarsResult<Bose<T>> {
         if self.dimensions < T::from(2).unwrap()
             || self.dimensions > self.prime_base + T::from(1).unwrap()
         {
             return Err(OarsError::InvalidParams("Invalid dimensions".into()));
         }
-
-        if !is_prime(self.prime_base.to_u64().unwrap()) {
-            return Err(OarsError::InvalidParams("Base is not prime".into()));
-        }
         Ok(Bose {
             prime_base: self.prime_base,
             dimensions: self.dimensions,
         })
     }
+
+    /// Check the parameters, generate the orthogonal array, and verify that the generated array is
+    /// valid.
+    ///
+    /// This is the same as calling `verify` then `gen`, but it also catches bugs in the
+    /// constructor that would produce an invalid array, at the cost of verifying the output.
+    ///
+    /// ```
+    /// use oars::prelude::*;
+    /// use oars::constructors::BoseChecked;
+    /// # fn main() -> OarsResult<()> {
+    /// let bose = BoseChecked {
+    ///     prime_base: 3,
+    ///     dimensions: 2,
+    /// };
+    /// let oa = bose.verify_strict()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn verify_strict(self) -> OAResult<T> {
+        ensure_valid(self.verify()?.gen()?)
+    }
 }
 
 /// Generate an orthogonal array with any prime base and a strength of 2
@@ -78,49 +116,140 @@ pub struct Bose<T: Integer> {
     pub dimensions: T,
 }
 
-impl<T: Integer> OAConstructor<T> for Bose<T> {
-    fn gen(&self) -> OAResult<T> {
-        let n = pow(self.prime_base, 2);
-        let mut points =
-            Array2::<T>::zeros((n.to_usize().unwrap(), self.dimensions.to_usize().unwrap()));
-
-        // Initialize dims 1 and 2 with the special construction technique
-        for i in 0..n.to_usize().unwrap() {
-            points[[i as usize, 0]] = T::from(i).unwrap() / self.prime_base;
-            points[[i as usize, 1]] = T::from(i).unwrap() % self.prime_base;
-        }
+impl<T: Integer> Bose<T> {
+    /// Get the largest number of dimensions that the Bose construction can provide for some base,
+    /// which is `prime_base + 1`. The number of runs is always `prime_base^2`, regardless of the
+    /// number of dimensions.
+    pub fn max_dimensions_for_runs(prime_base: T) -> T {
+        prime_base + T::from(1).unwrap()
+    }
+
+    /// Get the number of runs, `prime_base^2`, returning an error if it overflows `T` or `usize`
+    fn runs(&self) -> OarsResult<usize> {
+        as_usize(checked_pow(self.prime_base, 2).ok_or_else(|| {
+            OarsError::InvalidParams(
+                "`prime_base^2` overflows the range of the integer type".to_owned(),
+            )
+        })?)
+    }
 
-        for i in 0..n.to_usize().unwrap() {
-            for j in 2..self.dimensions.to_usize().unwrap() {
-                points[[i, j]] = (points[[i, 0]]
-                    + T::from(j - 1).unwrap() * points[[i as usize, 1]])
-                    % self.prime_base;
+    /// Generate the orthogonal array, invoking `cb` with the fraction of runs that have been
+    /// filled in so far.
+    ///
+    /// The callback is invoked periodically as the runs are filled in, and it is always invoked
+    /// with a value of 1.0 once the array is complete. This is meant to drive progress bars when
+    /// generating large arrays. `gen` is the same, but without a callback.
+    pub fn gen_with_progress<F: FnMut(f64)>(&self, mut cb: F) -> OAResult<T> {
+        // The multiplier for the last column is `dimensions - 2`, which wraps around modulo
+        // `prime_base` if there are more than `prime_base + 1` dimensions, duplicating an earlier
+        // column. The checked constructor rejects these parameters.
+        debug_assert!(
+            self.dimensions <= self.prime_base + T::from(1).unwrap(),
+            "Bose construction can't have more than `prime_base + 1` dimensions"
+        );
+        let n = self.runs()?;
+        let dimensions = as_usize(self.dimensions)?;
+        let mut points = Array2::<T>::zeros((n, dimensions));
+        let multipliers: Vec<T> = (2..dimensions)
+            .map(|j| from_usize_checked(j - 1))
+            .collect::<OarsResult<_>>()?;
+        // Report progress roughly every percent, so large arrays don't spend their time in the
+        // callback
+        let interval = (n / 100).max(1);
+
+        for i in 0..n {
+            // Initialize dims 1 and 2 with the special construction technique
+            let i_t: T = from_usize_checked(i)?;
+            points[[i, 0]] = i_t / self.prime_base;
+            points[[i, 1]] = i_t % self.prime_base;
+
+            for (j, &multiplier) in multipliers.iter().enumerate() {
+                points[[i, j + 2]] =
+                    (points[[i, 0]] + multiplier * points[[i, 1]]) % self.prime_base;
+            }
+
+            if (i + 1) % interval == 0 && i + 1 < n {
+                cb((i + 1) as f64 / n as f64);
             }
         }
+        cb(1.0);
 
         Ok(OA {
             strength: T::from(2).unwrap(),
             levels: self.prime_base,
             factors: self.dimensions,
-            index: T::from(1).unwrap(),
+            index: compute_index(n, self.prime_base, T::from(2).unwrap())?,
             points,
         })
     }
+
+    /// Generate the orthogonal array, then randomly permute its columns and relabel the levels of
+    /// each column.
+    ///
+    /// Permuting the columns and relabeling the levels of a column both preserve the strength of
+    /// the array, so this yields a random member of the equivalence class of the array that `gen`
+    /// produces, which is still a valid orthogonal array.
+    pub fn gen_randomized(&self, rng: &mut impl Rng) -> OAResult<T> {
+        let mut oa = self.gen()?;
+        let levels = as_usize(self.prime_base)?;
+        let (_, factors) = oa.points.dim();
+        let mut col_perm = PermutationVector::new(factors);
+        col_perm.shuffle(rng);
+        let order: Vec<usize> = (0..factors).map(|j| col_perm[j]).collect();
+        oa.points = oa.points.select(Axis(1), &order);
+
+        for mut col in oa.points.columns_mut() {
+            let mut labels = PermutationVector::new(levels);
+            labels.shuffle(rng);
+
+            for x in col.iter_mut() {
+                *x = from_usize_checked(labels[as_usize(*x)?])?;
+            }
+        }
+        Ok(oa)
+    }
+}
+
+impl<T: Integer> OAConstructor<T> for Bose<T> {
+    fn gen(&self) -> OAResult<T> {
+        self.gen_with_progress(|_| ())
+    }
+
+    fn describe(&self) -> String {
+        describe_construction(
+            "Bose",
+            &[("p", self.prime_base), ("d", self.dimensions)],
+            checked_pow(self.prime_base, 2),
+            self.dimensions,
+            self.prime_base,
+            T::from(2).unwrap(),
+            T::from(1).unwrap(),
+        )
+    }
 }
 
 #[cfg(feature = "parallel")]
 impl<T: Integer> ParOAConstructor<T> for Bose<T> {
     fn gen_par(&self) -> OAResult<T> {
-        let n = pow(self.prime_base, 2);
+        debug_assert!(
+            self.dimensions <= self.prime_base + T::from(1).unwrap(),
+            "Bose construction can't have more than `prime_base + 1` dimensions"
+        );
+        let n = self.runs()?;
 
         // We create two different arrays: the first two columns and the rest, because the latter
         // is dependent on the first, so each array is constructed in parallel and then
         // concatenated
-        let mut initial_points = Array2::<T>::zeros((n.to_usize().unwrap(), 2));
-        let mut points = Array2::<T>::zeros((
-            n.to_usize().unwrap(),
-            self.dimensions.to_usize().unwrap() - 2,
-        ));
+        let dimensions = as_usize(self.dimensions)?;
+        let mut initial_points = Array2::<T>::zeros((n, 2));
+        let mut points = Array2::<T>::zeros((n, dimensions - 2));
+
+        // Convert the indices up front, so a conversion failure is returned as an error instead of
+        // panicking in a worker thread
+        let row_labels: Vec<T> = (0..n).map(from_usize_checked).collect::<OarsResult<_>>()?;
+        let multipliers: Vec<T> = (2..dimensions)
+            .map(|j| from_usize_checked(j - 1))
+            .collect::<OarsResult<_>>()?;
 
         // Initialize the first two dimensions first, since all subsequent dimensions depend on the
         // these dims
@@ -133,8 +262,8 @@ impl<T: Integer> ParOAConstructor<T> for Bose<T> {
                     .into_par_iter()
                     .enumerate()
                     .for_each(|(row_idx, mut row)| match col_idx {
-                        0 => row[[row_idx; 0]] = T::from(row_idx).unwrap() / self.prime_base,
-                        1 => row[[row_idx; 0]] = T::from(row_idx).unwrap() % self.prime_base,
+                        0 => row[[row_idx; 0]] = row_labels[row_idx] / self.prime_base,
+                        1 => row[[row_idx; 0]] = row_labels[row_idx] % self.prime_base,
                         _ => panic!("A column besides 0 or 1 was reached, which is impossible"),
                     })
             });
@@ -151,7 +280,7 @@ impl<T: Integer> ParOAConstructor<T> for Bose<T> {
                     .enumerate()
                     .for_each(|(row_idx, mut row)| {
                         row[[row_idx; 0]] = (initial_points[[row_idx, 0]]
-                            + T::from(col_idx + 1).unwrap() * initial_points[[row_idx, 1]])
+                            + multipliers[col_idx] * initial_points[[row_idx, 1]])
                             % self.prime_base;
                     })
             });
@@ -160,7 +289,7 @@ impl<T: Integer> ParOAConstructor<T> for Bose<T> {
             strength: T::from(2).unwrap(),
             levels: self.prime_base,
             factors: self.dimensions,
-            index: T::from(1).unwrap(),
+            index: compute_index(n, self.prime_base, T::from(2).unwrap())?,
             points,
         })
     }
@@ -169,7 +298,10 @@ impl<T: Integer> ParOAConstructor<T> for Bose<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::oa::verify;
     use ndarray::arr2;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
 
     #[test]
     fn bose_init_2() {
@@ -214,4 +346,143 @@ mod tests {
         ]);
         assert!(oa.points == ground_truth);
     }
+
+    #[test]
+    fn bose_describe() {
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        assert!(bose.describe() == "Bose (p=3, d=3) -> OA(9, 3, 3, 2, 1)");
+
+        // 17^2 overflows a u8, so the number of runs is unknown
+        let bose: Bose<u8> = Bose {
+            prime_base: 17,
+            dimensions: 3,
+        };
+        assert!(bose.describe() == "Bose (p=17, d=3) -> OA(?, 3, 17, 2, 1)");
+    }
+
+    #[test]
+    fn bose_negative_base() {
+        let bose: BoseChecked<i32> = BoseChecked {
+            prime_base: -3,
+            dimensions: 2,
+        };
+        assert!(bose.verify().is_err());
+    }
+
+    #[test]
+    fn bose_runs_overflow() {
+        // 17^2 doesn't fit in a u8
+        let bose: Bose<u8> = Bose {
+            prime_base: 17,
+            dimensions: 2,
+        };
+        assert!(bose.gen().is_err());
+        #[cfg(feature = "parallel")]
+        assert!(bose.gen_par().is_err());
+    }
+
+    #[test]
+    fn bose_gen_with_progress() {
+        let bose = Bose {
+            prime_base: 13,
+            dimensions: 5,
+        };
+        let mut progress = Vec::new();
+        let oa = bose.gen_with_progress(|x| progress.push(x)).unwrap();
+        assert!(oa.points == bose.gen().unwrap().points);
+        assert!(!progress.is_empty());
+        assert!(progress.windows(2).all(|w| w[0] < w[1]));
+        assert!(*progress.last().unwrap() == 1.0);
+    }
+
+    #[test]
+    fn bose_verify_strict() {
+        let bose = BoseChecked {
+            prime_base: 5,
+            dimensions: 4,
+        };
+        assert!(bose.verify_strict().unwrap().points.dim() == (25, 4));
+
+        let bose = BoseChecked {
+            prime_base: 4,
+            dimensions: 4,
+        };
+        assert!(bose.verify_strict().is_err());
+    }
+
+    #[test]
+    fn bose_max_dimensions() {
+        assert!(Bose::max_dimensions_for_runs(5) == 6);
+
+        let bose = BoseChecked {
+            prime_base: 5,
+            dimensions: Bose::max_dimensions_for_runs(5),
+        };
+        assert!(bose.verify().is_ok());
+    }
+
+    #[test]
+    fn bose_gen_randomized() {
+        let bose = Bose {
+            prime_base: 5,
+            dimensions: 6,
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        let oa = bose.gen_randomized(&mut rng).unwrap();
+        assert!(oa.points != bose.gen().unwrap().points);
+        assert!(oa.points.dim() == (25, 6));
+        assert!(verify(&oa).unwrap());
+    }
+
+    #[test]
+    fn bose_assume_prime() {
+        let bose = BoseChecked {
+            prime_base: 5,
+            dimensions: 6,
+        };
+        let bose = bose.assume_prime().unwrap();
+        assert!(bose.prime_base == 5 && bose.dimensions == 6);
+
+        // The base isn't checked, so a composite base is accepted
+        let bose = BoseChecked {
+            prime_base: 4,
+            dimensions: 3,
+        };
+        assert!(bose.assume_prime().is_ok());
+
+        let bose = BoseChecked {
+            prime_base: 5,
+            dimensions: 7,
+        };
+        assert!(bose.assume_prime().is_err());
+    }
+
+    #[test]
+    fn bose_two_dimensions() {
+        let bose = Bose {
+            prime_base: 5,
+            dimensions: 2,
+        };
+        let oa = bose.gen().unwrap();
+        assert!(oa.points.dim() == (25, 2));
+        assert!(verify(&oa).unwrap());
+    }
+
+    /// The unchecked constructor doesn't reject too many dimensions. This trips a debug
+    /// assertion in debug builds, and yields an invalid array in release builds, since the
+    /// multiplier for the last column wraps around to 0 and duplicates the first column.
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic)]
+    fn bose_unchecked_too_many_dimensions() {
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 5,
+        };
+        let oa = bose.gen().unwrap();
+        assert!(oa.points.column(4) == oa.points.column(0));
+        assert!(!verify(&oa).unwrap());
+    }
 }
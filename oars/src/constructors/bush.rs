@@ -1,6 +1,10 @@
-use crate::oa::{OAConstructor, OAResult, OA};
-use crate::utils::{poly_eval, to_base_fixed, Integer, OarsError, OarsResult};
-use ndarray::Array2;
+use super::describe_construction;
+use crate::oa::{compute_index, ensure_valid, OAConstructor, OAResult, OA};
+use crate::utils::{
+    as_usize, checked_pow, from_usize_checked, poly_eval, to_base_fixed, Integer, OarsError,
+    OarsResult,
+};
+use ndarray::{Array2, ArrayViewMut1};
 use num::pow::pow;
 use oars_proc_macro::Checked;
 use primes::is_prime;
@@ -10,10 +14,7 @@ use std::cmp::min;
 use crate::oa::ParOAConstructor;
 
 #[cfg(feature = "parallel")]
-use ndarray::{concatenate, parallel::prelude::*, Axis};
-
-#[cfg(feature = "parallel")]
-use rayon::iter::IntoParallelIterator;
+use ndarray::{parallel::prelude::*, Axis};
 
 impl<T: Integer> BushChecked<T> {
     /// Verify that the parameters for Bush construction are valid
@@ -37,10 +38,24 @@ impl<T: Integer> BushChecked<T> {
     /// # }
     /// ```
     pub fn verify(self) -> OarsResult<Bush<T>> {
-        if !is_prime(self.prime_base.to_u64().unwrap()) {
+        let base = self
+            .prime_base
+            .to_u64()
+            .ok_or_else(|| OarsError::InvalidParams("Base out of range".to_owned()))?;
+
+        if !is_prime(base) {
             return Err(OarsError::InvalidParams("Base is not prime".to_owned()));
         }
+        self.assume_prime()
+    }
 
+    /// Check every parameter for Bush construction except for the primality of the base.
+    ///
+    /// This is meant for generating many arrays with a base that is already known to be prime,
+    /// where checking the primality of the base every time is redundant. The caller is
+    /// responsible for making sure that `prime_base` is prime: if it isn't, the resulting
+    /// orthogonal array will be invalid, just like using `Bush` directly.
+    pub fn assume_prime(self) -> OarsResult<Bush<T>> {
         if self.dimensions < T::from(2).unwrap()
             || self.dimensions > self.prime_base + T::from(1).unwrap()
         {
@@ -54,12 +69,43 @@ impl<T: Integer> BushChecked<T> {
                 "`strength` must be between 1 and `prime_base` (inclusive)".to_owned(),
             ));
         }
+
+        // The number of runs is `prime_base^strength`, which needs to fit in both `T` and `usize`
+        let runs = checked_pow(self.prime_base, as_usize(self.strength)?).ok_or_else(|| {
+            OarsError::InvalidParams(
+                "`prime_base^strength` overflows the range of the integer type".to_owned(),
+            )
+        })?;
+        as_usize(runs)?;
         Ok(Bush {
             strength: self.strength,
             prime_base: self.prime_base,
             dimensions: self.dimensions,
         })
     }
+
+    /// Check the parameters, generate the orthogonal array, and verify that the generated array is
+    /// valid.
+    ///
+    /// This is the same as calling `verify` then `gen`, but it also catches bugs in the
+    /// constructor that would produce an invalid array, at the cost of verifying the output.
+    ///
+    /// ```
+    /// use oars::prelude::*;
+    /// use oars::constructors::BushChecked;
+    /// # fn main() -> OarsResult<()> {
+    /// let bush = BushChecked {
+    ///     prime_base: 5,
+    ///     dimensions: 3,
+    ///     strength: 3,
+    /// };
+    /// let oa = bush.verify_strict()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn verify_strict(self) -> OAResult<T> {
+        ensure_valid(self.verify()?.gen()?)
+    }
 }
 
 /// Generate an orthogonal array with any prime base and a strength between 2 and p + 1
@@ -83,101 +129,129 @@ pub struct Bush<T: Integer> {
     pub dimensions: T,
 }
 
+impl<T: Integer> Bush<T> {
+    /// Get every strength that `BushChecked::verify` accepts for the given base, which is every
+    /// strength from 1 to `prime_base`, inclusive.
+    pub fn valid_strengths(prime_base: T) -> Vec<T> {
+        num::range_inclusive(T::from(1).unwrap(), prime_base).collect()
+    }
+
+    /// Get every number of dimensions that `BushChecked::verify` accepts for the given base, which
+    /// is every number from 2 to `prime_base + 1`, inclusive.
+    pub fn valid_dimensions(prime_base: T) -> Vec<T> {
+        num::range_inclusive(T::from(2).unwrap(), prime_base + T::from(1).unwrap()).collect()
+    }
+
+    /// Get the coefficients of the polynomial that Bush construction uses for a run, which are
+    /// the digits of the run index in base `prime_base`, truncated to `strength` digits.
+    ///
+    /// The coefficients are in ascending order of degree, so the first coefficient is the
+    /// constant term, and they are the digits of `i` starting from the least significant one.
+    /// Column `j` of the run is the polynomial evaluated at `j`, modulo `prime_base`.
+    ///
+    /// This returns an error if `i` is not less than the number of runs, `prime_base^strength`.
+    pub fn row_coefficients(&self, i: usize) -> OarsResult<Vec<T>> {
+        let runs = checked_pow(self.prime_base, as_usize(self.strength)?).ok_or_else(|| {
+            OarsError::InvalidParams(
+                "`prime_base^strength` overflows the range of the integer type".to_owned(),
+            )
+        })?;
+        let runs = as_usize(runs)?;
+
+        if i >= runs {
+            return Err(OarsError::InvalidParams(format!(
+                "Run {} is out of range for an array with {} runs",
+                i, runs
+            )));
+        }
+        Ok(to_base_fixed(
+            from_usize_checked(i)?,
+            self.prime_base,
+            self.strength,
+        ))
+    }
+
+    /// Fill in a row of the orthogonal array, given the index of the row.
+    ///
+    /// The digits of the row index in base `prime_base` are the coefficients of a polynomial, and
+    /// each column is the polynomial evaluated at the column index. There is a special case for
+    /// the last column if `dimensions` is equal to `prime_base + 1`, which is documented by Art
+    /// Owen: the last column is the leading coefficient of the polynomial.
+    fn fill_row(&self, row_idx: usize, mut row: ArrayViewMut1<T>) {
+        // The row index is less than the number of runs, which fits in `T`
+        let coeffs = to_base_fixed(T::from(row_idx).unwrap(), self.prime_base, self.strength);
+        let poly_dims = min(self.dimensions, self.prime_base).to_usize().unwrap();
+
+        for col_idx in 0..poly_dims {
+            row[col_idx] = poly_eval(&coeffs, T::from(col_idx).unwrap()) % self.prime_base;
+        }
+
+        if self.dimensions == self.prime_base + T::from(1).unwrap() {
+            row[poly_dims] = coeffs[coeffs.len() - 1];
+        }
+    }
+}
+
 impl<T: Integer> OAConstructor<T> for Bush<T> {
     fn gen(&self) -> OAResult<T> {
         let n = pow(self.prime_base, self.strength.to_usize().unwrap());
         let mut points =
             Array2::<T>::zeros((n.to_usize().unwrap(), self.dimensions.to_usize().unwrap()));
 
-        for i in 0..n.to_usize().unwrap() {
-            let coeffs = to_base_fixed(T::from(i).unwrap(), self.prime_base, self.strength);
-            let poly_dims = min(self.dimensions, self.prime_base);
-
-            for j in 0..poly_dims.to_usize().unwrap() {
-                points[[i as usize, j as usize]] =
-                    poly_eval(&coeffs, T::from(j).unwrap()) % self.prime_base;
-            }
-
-            if self.dimensions == self.prime_base + T::from(1).unwrap() {
-                points[[i, self.prime_base.to_usize().unwrap()]] =
-                    T::from(i - 1).unwrap() % self.prime_base;
-            }
+        for (i, row) in points.rows_mut().into_iter().enumerate() {
+            self.fill_row(i, row);
         }
 
         Ok(OA {
             strength: self.strength,
             levels: self.prime_base,
-            index: T::from(1).unwrap(),
+            index: compute_index(points.nrows(), self.prime_base, self.strength)?,
             factors: self.dimensions,
             points,
         })
     }
+
+    fn describe(&self) -> String {
+        let runs = self
+            .strength
+            .to_usize()
+            .and_then(|strength| checked_pow(self.prime_base, strength));
+        describe_construction(
+            "Bush",
+            &[
+                ("p", self.prime_base),
+                ("t", self.strength),
+                ("d", self.dimensions),
+            ],
+            runs,
+            self.dimensions,
+            self.prime_base,
+            self.strength,
+            T::from(1).unwrap(),
+        )
+    }
 }
 
 #[cfg(feature = "parallel")]
 impl<T: Integer> ParOAConstructor<T> for Bush<T> {
     fn gen_par(&self) -> OAResult<T> {
         let n = pow(self.prime_base, self.strength.to_usize().unwrap());
+        let mut points =
+            Array2::<T>::zeros((n.to_usize().unwrap(), self.dimensions.to_usize().unwrap()));
 
-        let mut initial_points = Array2::<T>::zeros((
-            n.to_usize().unwrap(),
-            min(
-                self.dimensions.to_usize().unwrap(),
-                self.prime_base.to_usize().unwrap(),
-            ),
-        ));
-
-        initial_points
+        // Every row only depends on its index, so the rows can be filled in independently
+        points
             .axis_iter_mut(Axis(0))
             .into_par_iter()
             .enumerate()
-            .for_each(|(row_idx, mut row)| {
-                let coeffs =
-                    to_base_fixed(T::from(row_idx).unwrap(), self.prime_base, self.strength);
-                row.axis_iter_mut(Axis(0))
-                    .into_par_iter()
-                    .enumerate()
-                    .for_each(|(col_idx, mut col)| {
-                        col[[col_idx; 0]] =
-                            poly_eval(&coeffs, T::from(col_idx).unwrap()) % self.prime_base;
-                    })
-            });
-
-        // There is a special case for the last column if it is equal to prime_base + 1, which is
-        // documented by Art Owen. We take care of this special case here, because otherwise it's
-        // an unnecessary calculation.
-        if self.dimensions == self.prime_base + T::from(1).unwrap() {
-            let mut last_col = Array2::<T>::zeros((n.to_usize().unwrap(), 1));
-
-            last_col
-                .axis_iter_mut(Axis(0))
-                .into_par_iter()
-                .enumerate()
-                .for_each(|(row_idx, mut row)| {
-                    row.axis_iter_mut(Axis(0))
-                        .into_par_iter()
-                        .enumerate()
-                        .for_each(|(_, mut col)| {
-                            col[[0 as usize; 0]] = T::from(row_idx - 1).unwrap() % self.prime_base;
-                        })
-                });
-            let points = concatenate(Axis(1), &[initial_points.view(), last_col.view()])?;
-
-            return Ok(OA {
-                strength: self.strength,
-                levels: self.prime_base,
-                index: T::from(1).unwrap(),
-                factors: self.dimensions,
-                points,
-            });
-        }
+            .for_each(|(row_idx, row)| self.fill_row(row_idx, row));
 
         Ok(OA {
             strength: self.strength,
             levels: self.prime_base,
-            index: T::from(1).unwrap(),
+            index: compute_index(points.nrows(), self.prime_base, self.strength)?,
             factors: self.dimensions,
-            points: initial_points,
+            points,
         })
     }
 }
@@ -233,4 +307,121 @@ mod tests {
         };
         assert!(bush.verify().is_err());
     }
+
+    #[test]
+    fn bush_run_count_overflow() {
+        let bush: BushChecked<i32> = BushChecked {
+            strength: 7,
+            prime_base: 23,
+            dimensions: 3,
+        };
+        assert!(bush.verify().is_err());
+
+        let bush: BushChecked<i32> = BushChecked {
+            strength: 6,
+            prime_base: 23,
+            dimensions: 3,
+        };
+        assert!(bush.verify().is_ok());
+    }
+
+    #[test]
+    fn bush_describe() {
+        let bush = Bush {
+            strength: 3,
+            prime_base: 5,
+            dimensions: 4,
+        };
+        assert!(bush.describe() == "Bush (p=5, t=3, d=4) -> OA(125, 4, 5, 3, 1)");
+    }
+
+    #[test]
+    fn bush_negative_base() {
+        let bush: BushChecked<i32> = BushChecked {
+            strength: 2,
+            prime_base: -3,
+            dimensions: 2,
+        };
+        assert!(bush.verify().is_err());
+    }
+
+    #[test]
+    fn bush_verify_strict() {
+        let bush = BushChecked {
+            strength: 3,
+            prime_base: 5,
+            dimensions: 6,
+        };
+        assert!(bush.verify_strict().unwrap().points.dim() == (125, 6));
+
+        let bush = BushChecked {
+            strength: 3,
+            prime_base: 5,
+            dimensions: 7,
+        };
+        assert!(bush.verify_strict().is_err());
+    }
+
+    #[test]
+    fn bush_valid_params() {
+        assert!(Bush::valid_strengths(5) == vec![1, 2, 3, 4, 5]);
+        assert!(Bush::valid_dimensions(5) == vec![2, 3, 4, 5, 6]);
+
+        for strength in Bush::valid_strengths(5) {
+            for dimensions in Bush::valid_dimensions(5) {
+                let bush = BushChecked {
+                    strength,
+                    prime_base: 5,
+                    dimensions,
+                };
+                assert!(bush.verify().is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn bush_assume_prime() {
+        let bush = BushChecked {
+            strength: 3,
+            prime_base: 5,
+            dimensions: 6,
+        };
+        let bush = bush.assume_prime().unwrap();
+        assert!(bush.prime_base == 5 && bush.strength == 3 && bush.dimensions == 6);
+
+        // The base isn't checked, so a composite base is accepted
+        let bush = BushChecked {
+            strength: 2,
+            prime_base: 4,
+            dimensions: 3,
+        };
+        assert!(bush.assume_prime().is_ok());
+
+        let bush = BushChecked {
+            strength: 6,
+            prime_base: 5,
+            dimensions: 3,
+        };
+        assert!(bush.assume_prime().is_err());
+    }
+
+    #[test]
+    fn bush_row_coefficients() {
+        let bush = Bush {
+            strength: 3,
+            prime_base: 3,
+            dimensions: 3,
+        };
+        assert!(bush.row_coefficients(0).unwrap() == vec![0, 0, 0]);
+        // 5 = 2 + 1 * 3
+        assert!(bush.row_coefficients(5).unwrap() == vec![2, 1, 0]);
+        // 22 = 1 + 1 * 3 + 2 * 9
+        assert!(bush.row_coefficients(22).unwrap() == vec![1, 1, 2]);
+        assert!(bush.row_coefficients(26).unwrap() == vec![2, 2, 2]);
+        assert!(bush.row_coefficients(27).is_err());
+
+        // Row 22 is 1 + x + 2x^2 evaluated at 0, 1, and 2, modulo 3
+        let oa = bush.gen().unwrap();
+        assert!(oa.points.row(22).to_vec() == vec![1, 1, 2]);
+    }
 }
@@ -0,0 +1,165 @@
+//! Measures of how uniformly a point set fills the unit hypercube.
+//!
+//! A lower discrepancy means that the points are spread more evenly, which generally leads to a
+//! lower error when the points are used for Monte Carlo integration. These are useful to compare
+//! designs, or different projections of the same design.
+
+use crate::utils::Float;
+use itertools::Itertools;
+use ndarray::Array2;
+
+/// Compute the centered $L_2$ discrepancy of a point set in $[0, 1)^d$, as defined by Hickernell
+/// in "A Generalized Discrepancy and Quadrature Error Bound" (1998).
+///
+/// Each row of `points` is a point. This uses the closed form expression for the squared
+/// discrepancy, which takes $O(n^2 d)$ time, and returns its square root. This returns NaN if the
+/// point set is empty.
+pub fn centered_l2_discrepancy<U: Float>(points: &Array2<U>) -> U {
+    let (n, d) = points.dim();
+
+    if n == 0 {
+        return U::nan();
+    }
+    let n = U::from(n).unwrap();
+    let one = U::one();
+    let half = U::from(0.5).unwrap();
+    let two = U::from(2).unwrap();
+    let dist = |x: U| (x - half).abs();
+
+    let single_sum = points
+        .rows()
+        .into_iter()
+        .map(|row| {
+            row.iter().fold(one, |acc, &x| {
+                acc * (one + half * dist(x) - half * dist(x).powi(2))
+            })
+        })
+        .fold(U::zero(), |acc, x| acc + x);
+    let double_sum = points
+        .rows()
+        .into_iter()
+        .map(|a| {
+            points
+                .rows()
+                .into_iter()
+                .map(|b| {
+                    a.iter().zip(b.iter()).fold(one, |acc, (&x, &y)| {
+                        acc * (one + half * dist(x) + half * dist(y) - half * (x - y).abs())
+                    })
+                })
+                .fold(U::zero(), |acc, x| acc + x)
+        })
+        .fold(U::zero(), |acc, x| acc + x);
+    let ratio = U::from(13).unwrap() / U::from(12).unwrap();
+    let squared = ratio.powi(d as i32) - two / n * single_sum + double_sum / n.powi(2);
+
+    // Rounding errors can make the squared discrepancy slightly negative for very uniform sets
+    squared.max(U::zero()).sqrt()
+}
+
+/// Compute the star discrepancy of a point set in $[0, 1)^d$.
+///
+/// The star discrepancy is the largest difference between the fraction of points inside of a box
+/// anchored at the origin and the volume of that box, over every such box. The supremum is
+/// attained at one of the boxes whose corner coordinates are coordinates of the points or 1, so
+/// this computes it exactly by checking every one of them. This takes $O(n^{d + 1} d)$ time, so it
+/// is only practical for small point sets in a few dimensions. This returns NaN if the point set
+/// is empty.
+pub fn star_discrepancy<U: Float>(points: &Array2<U>) -> U {
+    let n = points.nrows();
+
+    if n == 0 {
+        return U::nan();
+    }
+    let n_float = U::from(n).unwrap();
+
+    // The candidate coordinates of the corner of a box in each dimension
+    let coordinates: Vec<Vec<U>> = points
+        .columns()
+        .into_iter()
+        .map(|col| {
+            let mut values: Vec<U> = col
+                .iter()
+                .cloned()
+                .chain(std::iter::once(U::one()))
+                .collect();
+            values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            values.dedup();
+            values
+        })
+        .collect();
+
+    coordinates
+        .iter()
+        .map(|values| values.iter().cloned())
+        .multi_cartesian_product()
+        .map(|corner| {
+            let volume = corner.iter().fold(U::one(), |acc, &y| acc * y);
+            let (mut open, mut closed) = (0, 0);
+
+            for row in points.rows() {
+                if row.iter().zip(corner.iter()).all(|(&x, &y)| x <= y) {
+                    closed += 1;
+
+                    if row.iter().zip(corner.iter()).all(|(&x, &y)| x < y) {
+                        open += 1;
+                    }
+                }
+            }
+            let open = U::from(open).unwrap() / n_float;
+            let closed = U::from(closed).unwrap() / n_float;
+            (volume - open).max(closed - volume)
+        })
+        .fold(U::zero(), |acc, x| acc.max(x))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::arr2;
+
+    #[test]
+    fn test_centered_l2_single_point() {
+        // A single point in the center of the unit interval has a squared discrepancy of 1/12
+        let points = arr2(&[[0.5]]);
+        assert!((centered_l2_discrepancy(&points) - (1.0f64 / 12.0).sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_centered_l2_uniform_is_lower() {
+        let grid = Array2::from_shape_fn((16, 2), |(i, j)| {
+            let cell = if j == 0 { i / 4 } else { i % 4 };
+            (cell as f64 + 0.5) / 4.0
+        });
+        let clumped = Array2::from_elem((16, 2), 0.1);
+        assert!(centered_l2_discrepancy(&grid) < centered_l2_discrepancy(&clumped));
+        assert!(centered_l2_discrepancy(&Array2::<f64>::zeros((0, 2))).is_nan());
+    }
+
+    #[test]
+    fn test_star_discrepancy_1d() {
+        // The centered 1D grid with n points has a star discrepancy of 1 / (2n)
+        let points = Array2::from_shape_fn((4, 1), |(i, _)| (i as f64 + 0.5) / 4.0);
+        assert!((star_discrepancy(&points) - 0.125).abs() < 1e-12);
+
+        // A single point at the origin has a star discrepancy of 1
+        assert!((star_discrepancy(&arr2(&[[0.0f64, 0.0]])) - 1.0).abs() < 1e-12);
+        assert!(star_discrepancy(&Array2::<f64>::zeros((0, 2))).is_nan());
+    }
+
+    #[test]
+    fn test_discrepancy_f32() {
+        let grid = Array2::from_shape_fn((16, 2), |(i, j)| {
+            let cell = if j == 0 { i / 4 } else { i % 4 };
+            (cell as f32 + 0.5) / 4.0
+        });
+        let grid_f64 = grid.mapv(f64::from);
+
+        let l2: f32 = centered_l2_discrepancy(&grid);
+        assert!((f64::from(l2) - centered_l2_discrepancy(&grid_f64)).abs() < 1e-5);
+
+        let star: f32 = star_discrepancy(&grid);
+        assert!((f64::from(star) - star_discrepancy(&grid_f64)).abs() < 1e-5);
+        assert!(star > 0.0 && star < 1.0);
+    }
+}
@@ -0,0 +1,246 @@
+//! Linear algebra over prime fields.
+//!
+//! Linear orthogonal arrays are defined by a generator matrix over $\mathbb{Z}_p$, where $p$ is
+//! the number of levels. This module provides the operations on those matrices that such
+//! constructions need.
+
+use crate::utils::{Integer, OarsError, OarsResult};
+use ndarray::Array2;
+use primes::{factors_uniq, is_prime};
+
+/// Check whether a number is a power of a prime, such as $p^k$ for some prime $p$ and $k \ge 1$.
+///
+/// There is a finite field with $n$ elements if and only if $n$ is a prime power. This returns
+/// false for 0 and 1.
+pub fn is_prime_power(n: u64) -> bool {
+    factors_uniq(n).len() == 1
+}
+
+/// Compute the multiplicative inverse of `a` modulo the prime `p` with the extended Euclidean
+/// algorithm. `a` must not be a multiple of `p`.
+fn inverse_mod_p(a: u128, p: u128) -> u128 {
+    let (mut old_r, mut r) = (a as i128, p as i128);
+    let (mut old_s, mut s) = (1_i128, 0_i128);
+
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+    }
+    old_s.rem_euclid(p as i128) as u128
+}
+
+/// Row reduce a matrix over $\mathbb{Z}_p$ into reduced row echelon form in place, returning the
+/// column of the pivot in each of the nonzero rows.
+///
+/// Every entry must already be in the range $[0, p)$.
+fn rref(matrix: &mut Array2<u128>, p: u128) -> Vec<usize> {
+    let (rows, cols) = matrix.dim();
+    let mut pivots = Vec::new();
+
+    for col in 0..cols {
+        let row = pivots.len();
+
+        if row == rows {
+            break;
+        }
+
+        let pivot_row = match (row..rows).find(|&i| matrix[[i, col]] != 0) {
+            Some(i) => i,
+            None => continue,
+        };
+
+        for j in 0..cols {
+            matrix.swap([row, j], [pivot_row, j]);
+        }
+        let inverse = inverse_mod_p(matrix[[row, col]], p);
+        matrix.row_mut(row).mapv_inplace(|x| (x * inverse) % p);
+
+        for i in (0..rows).filter(|&i| i != row) {
+            let factor = matrix[[i, col]];
+
+            if factor == 0 {
+                continue;
+            }
+
+            for j in 0..cols {
+                // Subtracting modulo p is the same as adding the additive inverse
+                matrix[[i, j]] = (matrix[[i, j]] + (p - factor) * matrix[[row, j]]) % p;
+            }
+        }
+        pivots.push(col);
+    }
+    pivots
+}
+
+/// Check that the base is a prime number, and convert it to `u128`
+fn field_prime<T: Integer>(prime_base: T) -> OarsResult<u128> {
+    prime_base
+        .to_u64()
+        .filter(|&p| p > 1 && is_prime(p))
+        .map(u128::from)
+        .ok_or_else(|| OarsError::InvalidParams("Base is not prime".to_owned()))
+}
+
+/// Check that the base is prime, and convert a matrix over $\mathbb{Z}_p$ to `u128` so products
+/// of entries can't overflow.
+fn to_field_matrix<T: Integer>(
+    matrix: &Array2<T>,
+    prime_base: T,
+) -> OarsResult<(Array2<u128>, u128)> {
+    let p = field_prime(prime_base)?;
+    let mut converted = Array2::<u128>::zeros(matrix.dim());
+
+    for (x, &entry) in converted.iter_mut().zip(matrix.iter()) {
+        *x = entry.to_u128().filter(|&entry| entry < p).ok_or_else(|| {
+            OarsError::InvalidParams(
+                "Every entry of the matrix must be between 0 and `prime_base`".to_owned(),
+            )
+        })?;
+    }
+    Ok((converted, p))
+}
+
+/// Row reduce a matrix over $\mathbb{Z}_p$ into reduced row echelon form in place, and return its
+/// rank.
+///
+/// The entries are reduced modulo `prime_base` first, so negative entries are allowed. Every
+/// pivot is normalized to 1 with its multiplicative inverse modulo `prime_base`, and every other
+/// entry in a pivot column is eliminated.
+///
+/// This returns an error if `prime_base` is not prime, since $\mathbb{Z}_p$ is only a field when
+/// $p$ is prime. The matrix is left unchanged in that case.
+pub fn rref_mod_p<T: Integer>(matrix: &mut Array2<T>, prime_base: T) -> OarsResult<usize> {
+    // Check the base before reducing the entries, since reducing modulo 0 would panic
+    field_prime(prime_base)?;
+    let reduced = matrix.mapv(|x| x.mod_floor(&prime_base));
+    let (mut field_matrix, p) = to_field_matrix(&reduced, prime_base)?;
+    let rank = rref(&mut field_matrix, p).len();
+
+    for (x, &entry) in matrix.iter_mut().zip(field_matrix.iter()) {
+        *x = T::from(entry).unwrap();
+    }
+    Ok(rank)
+}
+
+/// Compute a parity-check matrix for the linear code over $\mathbb{Z}_p$ spanned by the rows of
+/// `generator`.
+///
+/// The rows of the result are a basis for the dual code, which is every vector that is orthogonal
+/// to every row of `generator` modulo `prime_base`. If the generator matrix has $n$ columns and
+/// rank $r$, the result has $n - r$ rows and $n$ columns. When the generator matrix is in the
+/// standard form $[I | A]$, the result is $[-A^T | I]$.
+///
+/// This returns an error if `prime_base` is not prime, or if an entry of the generator matrix is
+/// not between 0 and `prime_base`.
+pub fn dual_code<T: Integer>(generator: &Array2<T>, prime_base: T) -> OarsResult<Array2<T>> {
+    let (mut reduced, p) = to_field_matrix(generator, prime_base)?;
+    let pivots = rref(&mut reduced, p);
+    let cols = generator.ncols();
+    let free_cols: Vec<usize> = (0..cols).filter(|col| !pivots.contains(col)).collect();
+    let mut dual = Array2::<T>::zeros((free_cols.len(), cols));
+
+    // Every free column yields a basis vector of the dual code, which sets that free variable
+    // to 1 and solves for the pivot variables.
+    for (mut row, &free_col) in dual.rows_mut().into_iter().zip(free_cols.iter()) {
+        row[free_col] = T::from(1).unwrap();
+
+        for (i, &pivot_col) in pivots.iter().enumerate() {
+            row[pivot_col] = T::from((p - reduced[[i, free_col]]) % p).unwrap();
+        }
+    }
+    Ok(dual)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::arr2;
+
+    #[test]
+    fn test_is_prime_power() {
+        for n in [2, 3, 4, 5, 7, 8, 9, 16, 25, 27, 49, 121, 1024] {
+            assert!(is_prime_power(n));
+        }
+
+        for n in [0, 1, 6, 10, 12, 18, 36, 100] {
+            assert!(!is_prime_power(n));
+        }
+    }
+
+    #[test]
+    fn test_dual_code_gf2() {
+        // A [4, 2] binary code in standard form
+        let generator = arr2(&[[1, 0, 1, 1], [0, 1, 0, 1]]);
+        let dual = dual_code(&generator, 2).unwrap();
+        assert!(dual == arr2(&[[1, 0, 1, 0], [1, 1, 0, 1]]));
+
+        // Every row of the dual matrix is orthogonal to every row of the generator
+        assert!(generator.dot(&dual.t()).iter().all(|x| x % 2 == 0));
+    }
+
+    #[test]
+    fn test_dual_code_gf3() {
+        // The second row is a multiple of the first, so the code has rank 1
+        let generator = arr2(&[[1, 2, 0], [2, 1, 0]]);
+        let dual = dual_code(&generator, 3).unwrap();
+        assert!(dual.dim() == (2, 3));
+        assert!(generator.dot(&dual.t()).iter().all(|x| x % 3 == 0));
+    }
+
+    #[test]
+    fn test_dual_code_invalid() {
+        let generator = arr2(&[[1, 0, 1, 1], [0, 1, 0, 1]]);
+        assert!(dual_code(&generator, 4).is_err());
+
+        let generator = arr2(&[[1, 0, 2], [0, 1, 0]]);
+        assert!(dual_code(&generator, 2).is_err());
+    }
+
+    #[test]
+    fn test_rref_mod_p_gf3() {
+        let mut matrix = arr2(&[[1, 2, 0], [2, 1, 0], [0, 1, 1]]);
+        assert!(rref_mod_p(&mut matrix, 3).unwrap() == 2);
+        assert!(matrix == arr2(&[[1, 0, 1], [0, 1, 1], [0, 0, 0]]));
+
+        // Negative entries are reduced modulo p first
+        let mut matrix = arr2(&[[-1, 0], [0, -2]]);
+        assert!(rref_mod_p(&mut matrix, 3).unwrap() == 2);
+        assert!(matrix == arr2(&[[1, 0], [0, 1]]));
+    }
+
+    #[test]
+    fn test_rref_mod_p_gf5() {
+        let mut matrix = arr2(&[[1, 1, 0], [0, 1, 1], [1, 0, 1]]);
+        assert!(rref_mod_p(&mut matrix, 5).unwrap() == 3);
+        assert!(matrix == arr2(&[[1, 0, 0], [0, 1, 0], [0, 0, 1]]));
+
+        // The third row is the sum of the first two, so the rank is 2
+        let mut matrix = arr2(&[[1, 2, 3], [4, 0, 1], [0, 2, 4]]);
+        assert!(rref_mod_p(&mut matrix, 5).unwrap() == 2);
+        assert!(matrix == arr2(&[[1, 0, 4], [0, 1, 2], [0, 0, 0]]));
+
+        // Every row is a multiple of the first one, so the rank is 1
+        let mut matrix = arr2(&[[2, 4, 1, 3], [1, 2, 3, 4], [3, 1, 4, 2]]);
+        assert!(rref_mod_p(&mut matrix, 5).unwrap() == 1);
+        assert!(matrix == arr2(&[[1, 2, 3, 4], [0, 0, 0, 0], [0, 0, 0, 0]]));
+
+        let mut zeros = Array2::<u32>::zeros((2, 2));
+        assert!(rref_mod_p(&mut zeros, 5).unwrap() == 0);
+    }
+
+    #[test]
+    fn test_rref_mod_p_invalid_base() {
+        let original = arr2(&[[1, 2], [3, 4]]);
+
+        // A base of 0 would divide by zero if the entries were reduced before the base is checked
+        for base in [4, 0, 1, -3] {
+            let mut matrix = original.clone();
+            assert!(matches!(
+                rref_mod_p(&mut matrix, base),
+                Err(OarsError::InvalidParams(_))
+            ));
+            assert!(matrix == original);
+        }
+    }
+}
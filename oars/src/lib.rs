@@ -51,6 +51,8 @@
 //! ```
 
 pub mod constructors;
+pub mod discrepancy;
+pub mod galois;
 pub mod oa;
 mod perm_vec;
 pub mod prelude;
@@ -59,4 +61,4 @@ mod utils;
 
 // Export these types because any consumer of this library will need to have these type definitions
 // in order to use the OA/SOA definitions and constructors
-pub use utils::{ErrorKind, Float, Integer, OarsError, OarsResult};
+pub use utils::{ErrorKind, Float, Integer, OarsError, OarsResult, RngConfig};
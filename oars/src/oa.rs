@@ -4,23 +4,46 @@
 //! defines traits for serial and parallel OA construction, which are utilized by OA constructors
 //! and can be extended by users to define new OA construction methods.
 
+use crate::discrepancy::centered_l2_discrepancy;
+use crate::galois::is_prime_power;
 use crate::perm_vec::PermutationVector;
-use crate::utils::{Float, Integer};
+use crate::utils::{
+    as_usize, checked_pow, format_integer, from_usize_checked, Float, Integer, RngConfig,
+};
 use crate::utils::{OarsError, OarsResult};
 use itertools::Itertools;
-use ndarray::Array2;
-use num::{pow, ToPrimitive};
+use ndarray::{concatenate, Array2, ArrayView1, Axis};
+use num::pow;
 use rand::prelude::*;
 use std::ops::Index;
 
 #[cfg(feature = "serialize")]
 use serde_derive::{Deserialize, Serialize};
 
-use std::collections::HashMap;
+#[cfg(feature = "parallel")]
+use ndarray::parallel::prelude::*;
+
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::hash::Hash;
+
+// Reading and writing arrays, the distance-based metrics for point sets, and the statically sized
+// wrapper are declared in separate files to keep this module focused on the definition of an
+// orthogonal array
+mod distance;
+mod fixed;
+mod io;
+
+pub use distance::{fill_distance, maximin_score, min_distance, Metric};
+pub use fixed::OaN;
+pub use io::{read_csv_verified, read_owen_table, write_gnuplot, write_gnuplot_blocks};
 
 /// The definition of an orthogonal array with its point set and parameters.
-#[derive(Debug)]
+///
+/// Orthogonal arrays can be compared and hashed by their content, which includes the order of the
+/// runs. Use `OA::canonicalize` first if two arrays with the same runs in a different order should
+/// be treated as equal.
+#[derive(Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct OA<T: Integer> {
     /// The size of the set `X` that the array can select elements from.
@@ -65,6 +88,849 @@ impl<T: Integer> Index<[usize; 2]> for OA<T> {
     }
 }
 
+/// The maximum number of partial subsets that `OA::reduce_strength` visits before giving up
+pub const REDUCE_STRENGTH_SEARCH_LIMIT: usize = 1 << 20;
+
+/// The maximum number of partial assignments that `OA::resolvable_blocks` visits before giving up
+pub const RESOLVABLE_BLOCKS_SEARCH_LIMIT: usize = 1 << 20;
+
+impl<T: Integer> OA<T> {
+    /// Select a subset of the runs that forms an orthogonal array with a lower strength.
+    ///
+    /// Any orthogonal array of strength $t$ is also an orthogonal array of every strength below
+    /// $t$, just with a higher index. This method instead searches for $s^{t'}$ runs (where $t'$
+    /// is `target`) that form an orthogonal array of strength $t'$ and index 1, which reduces the
+    /// number of runs in the array. The runs are searched in a random order determined by `rng`,
+    /// so different generators may select different subsets.
+    ///
+    /// The subset is found with a backtracking search, which can take exponential time for large
+    /// arrays, so the search gives up after visiting `REDUCE_STRENGTH_SEARCH_LIMIT` partial
+    /// subsets. Use `reduce_strength_with_limit` to choose another limit.
+    ///
+    /// This returns an error if `target` is not between 1 and the strength of the array, if an
+    /// entry is outside of the range of levels, if no subset of the runs is exactly balanced at
+    /// the lower strength, or if the search limit is reached.
+    pub fn reduce_strength(&self, target: T, rng: &mut impl Rng) -> OarsResult<OA<T>> {
+        self.reduce_strength_with_limit(target, REDUCE_STRENGTH_SEARCH_LIMIT, rng)
+    }
+
+    /// Select a subset of the runs that forms an orthogonal array with a lower strength, visiting
+    /// at most `max_nodes` partial subsets during the search.
+    ///
+    /// This is the same as `reduce_strength`, and returns a `RuntimeError` if the search visits
+    /// more than `max_nodes` partial subsets without finding a balanced one.
+    pub fn reduce_strength_with_limit(
+        &self,
+        target: T,
+        max_nodes: usize,
+        rng: &mut impl Rng,
+    ) -> OarsResult<OA<T>> {
+        if target < T::from(1).unwrap() || target > self.strength {
+            return Err(OarsError::InvalidParams(
+                "`target` must be between 1 and the strength of the array (inclusive)".to_owned(),
+            ));
+        }
+        let levels = as_usize(self.levels)?;
+        let target_usize = as_usize(target)?;
+        let needed = checked_pow(levels, target_usize).ok_or_else(|| {
+            OarsError::InvalidParams("The reduced array has too many runs".to_owned())
+        })?;
+        let col_combos: Vec<Vec<usize>> = (0..self.points.ncols())
+            .combinations(target_usize)
+            .collect();
+
+        // Precompute the index of the tuple that each row has for every combination of columns,
+        // so the search only has to do lookups
+        let tuples: Vec<Vec<usize>> = level_indices(&self.points, self.levels)?
+            .iter()
+            .map(|row| {
+                col_combos
+                    .iter()
+                    .map(|combo| {
+                        combo
+                            .iter()
+                            .enumerate()
+                            .map(|(power, &col)| row[col] * pow(levels, power))
+                            .sum()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut order: Vec<usize> = (0..self.points.nrows()).collect();
+        order.shuffle(rng);
+        let mut seen = vec![vec![false; needed]; col_combos.len()];
+        let mut selected = Vec::with_capacity(needed);
+        let mut budget = max_nodes;
+
+        if !balanced_subset(
+            &tuples,
+            &order,
+            needed,
+            &mut seen,
+            &mut selected,
+            &mut budget,
+        )? {
+            return Err(OarsError::RuntimeError(
+                "No subset of the runs is balanced at the target strength".to_owned(),
+            ));
+        }
+        // Keep the selected runs in the same relative order as the original array
+        selected.sort_unstable();
+
+        Ok(OA {
+            levels: self.levels,
+            strength: target,
+            factors: self.factors,
+            index: T::from(1).unwrap(),
+            points: self.points.select(Axis(0), &selected),
+        })
+    }
+
+    /// Append a column where every entry is `value`, and increment the number of factors.
+    ///
+    /// This is useful for shaping data, such as adding an intercept column for a regression
+    /// design. Note that the result is no longer an orthogonal array: the strength guarantee does
+    /// not hold for any combination of columns that includes the constant column, so `verify`
+    /// will fail on the result.
+    pub fn append_constant_column(&mut self, value: T) {
+        let (rows, cols) = self.points.dim();
+        self.points = Array2::from_shape_fn((rows, cols + 1), |(i, j)| {
+            if j < cols {
+                self.points[[i, j]]
+            } else {
+                value
+            }
+        });
+        self.factors = self.factors + T::from(1).unwrap();
+    }
+
+    /// Map every entry of the array to a new value, such as a real-world coded level.
+    ///
+    /// For example, this can map the levels `{0, 1, 2}` to `{-1, 0, 1}`. This returns a plain
+    /// array rather than an `OA`, because the mapping may not preserve the numeric structure that
+    /// `verify` and `normalize` rely on.
+    pub fn map_levels<U: Integer, F: Fn(T) -> U>(&self, f: F) -> Array2<U> {
+        self.points.mapv(f)
+    }
+
+    /// Consume the orthogonal array and return the internal array of points, discarding the
+    /// metadata.
+    ///
+    /// This moves the array out of the struct, which avoids having to clone `points`.
+    pub fn into_points(self) -> Array2<T> {
+        self.points
+    }
+
+    /// Get the `j`-th column of the orthogonal array as an owned vector.
+    ///
+    /// This returns an error if `j` is out of bounds.
+    pub fn column(&self, j: usize) -> OarsResult<Vec<T>> {
+        if j >= self.points.ncols() {
+            return Err(OarsError::InvalidParams(format!(
+                "Column {} is out of bounds for an array with {} factors",
+                j,
+                self.points.ncols()
+            )));
+        }
+        Ok(self.points.column(j).to_vec())
+    }
+
+    /// Iterate over the columns of the orthogonal array, yielding each one as an owned vector.
+    pub fn columns(&self) -> impl Iterator<Item = Vec<T>> + '_ {
+        self.points.columns().into_iter().map(|col| col.to_vec())
+    }
+
+    /// Stack `times` copies of the runs of the orthogonal array.
+    ///
+    /// Replicating an orthogonal array preserves its strength and levels, and multiplies its index
+    /// by `times`. This is a simple way to get an orthogonal array with an index greater than 1.
+    ///
+    /// This returns an error if the number of runs or the index of the result is out of range.
+    pub fn replicate(&self, times: usize) -> OAResult<T> {
+        let (rows, cols) = self.points.dim();
+        let overflow =
+            || OarsError::InvalidParams("The replicated array has too many runs".to_owned());
+        let index = from_usize_checked(
+            as_usize(self.index)?
+                .checked_mul(times)
+                .ok_or_else(overflow)?,
+        )?;
+        let runs = rows.checked_mul(times).ok_or_else(overflow)?;
+
+        Ok(OA {
+            levels: self.levels,
+            strength: self.strength,
+            factors: self.factors,
+            index,
+            points: Array2::from_shape_fn((runs, cols), |(i, j)| self.points[[i % rows, j]]),
+        })
+    }
+
+    /// Convert the orthogonal array into a point set that is ready to use for sampling.
+    ///
+    /// This optionally verifies the orthogonal array first, returning an error if it is not
+    /// valid, then normalizes it with `normalize_with_rng` using the supplied jitter and random
+    /// number generator.
+    pub fn sample<U: Float>(
+        &self,
+        jitter: U,
+        randomize: bool,
+        verify_first: bool,
+        rng: &mut impl Rng,
+    ) -> OarsResult<Array2<U>> {
+        if verify_first && !verify(self)? {
+            return Err(OarsError::InvalidParams(
+                "The points are not a valid orthogonal array".to_owned(),
+            ));
+        }
+        normalize_with_rng(self, jitter, randomize, rng)
+    }
+
+    /// Sort the runs of the orthogonal array lexicographically, in place.
+    ///
+    /// The order of the runs doesn't affect whether the array is a valid orthogonal array, so the
+    /// metadata is left untouched. Canonicalizing two arrays that only differ in the order of
+    /// their runs makes them identical, so they can be compared directly.
+    pub fn canonicalize(&mut self) {
+        let order = sorted_row_order(&self.points);
+        self.points = self.points.select(Axis(0), &order);
+    }
+
+    /// Get the number of distinct $t$-tuples that every combination of `strength` columns should
+    /// contain, which is `levels^strength`.
+    ///
+    /// This saturates at `u64::MAX` instead of overflowing.
+    pub fn expected_tuple_count(&self) -> u64 {
+        self.levels
+            .to_u64()
+            .unwrap_or(u64::MAX)
+            .saturating_pow(self.strength.to_u32().unwrap_or(u32::MAX))
+    }
+
+    /// Get the number of runs that the orthogonal array should have according to its metadata,
+    /// which is `index * levels^strength`.
+    ///
+    /// This saturates at `u64::MAX` instead of overflowing.
+    pub fn expected_runs(&self) -> u64 {
+        self.index
+            .to_u64()
+            .unwrap_or(u64::MAX)
+            .saturating_mul(self.expected_tuple_count())
+    }
+
+    /// Get the number of levels that the points actually use, which is the largest entry plus
+    /// one.
+    ///
+    /// Unlike the `levels` field, this is computed from `points`, so the two can be compared to
+    /// detect arrays with inconsistent metadata. This is 0 if the array has no entries.
+    pub fn observed_levels(&self) -> T {
+        self.points
+            .iter()
+            .copied()
+            .max()
+            .map_or(T::from(0).unwrap(), |x| x + T::from(1).unwrap())
+    }
+
+    /// Check whether the number of levels is a prime power.
+    ///
+    /// Many constructions, such as the ones that build on finite fields, are only defined for a
+    /// prime power number of levels, so this can be used to warn early about an array that was
+    /// built from raw points.
+    pub fn is_prime_power_levels(&self) -> bool {
+        self.levels.to_u64().is_some_and(is_prime_power)
+    }
+
+    /// Stack the runs of another orthogonal array below the runs of this one.
+    ///
+    /// Both arrays must have the same number of factors, levels, and strength. The index of the
+    /// result is the sum of both indices. The combined array is verified, and this returns an
+    /// error if it is not a valid orthogonal array.
+    pub fn vstack(&self, other: &OA<T>) -> OarsResult<OA<T>> {
+        if self.factors != other.factors
+            || self.levels != other.levels
+            || self.strength != other.strength
+        {
+            return Err(OarsError::InvalidParams(
+                "Both arrays must have the same number of factors, levels, and strength".to_owned(),
+            ));
+        }
+        let oa = OA {
+            levels: self.levels,
+            strength: self.strength,
+            factors: self.factors,
+            index: self.index + other.index,
+            points: concatenate(Axis(0), &[self.points.view(), other.points.view()])?,
+        };
+
+        if !verify(&oa)? {
+            return Err(OarsError::InvalidParams(
+                "The stacked arrays are not a valid orthogonal array".to_owned(),
+            ));
+        }
+        Ok(oa)
+    }
+
+    /// Format the orthogonal array as a LaTeX `tabular` environment.
+    ///
+    /// The table has a column for each factor and a row for each run, and is wrapped in a `table`
+    /// environment with a caption that lists the parameters of the array, in the form
+    /// $OA(n, m, s, t)$ with its index.
+    pub fn to_latex(&self) -> String {
+        let fmt = format_integer::<T>;
+        let mut latex = String::new();
+
+        latex.push_str("\\begin{table}\n\\centering\n");
+        latex.push_str(&format!(
+            "\\caption{{$OA({}, {}, {}, {})$ with index {}}}\n",
+            self.points.nrows(),
+            fmt(self.factors),
+            fmt(self.levels),
+            fmt(self.strength),
+            fmt(self.index)
+        ));
+        latex.push_str(&format!(
+            "\\begin{{tabular}}{{{}}}\n",
+            "c".repeat(self.points.ncols())
+        ));
+
+        for row in self.points.rows() {
+            let entries: Vec<String> = row.iter().map(|&x| fmt(x)).collect();
+            latex.push_str(&format!("{} \\\\\n", entries.join(" & ")));
+        }
+        latex.push_str("\\end{tabular}\n\\end{table}\n");
+        latex
+    }
+
+    /// Get the runs of the full factorial design that are not in this array.
+    ///
+    /// The full factorial design over `factors` factors with `levels` levels contains every
+    /// possible run exactly once, so it has `levels^factors` runs. If this array is a fraction of
+    /// the full factorial with index $\lambda$, then the complement is an orthogonal array with
+    /// the same strength and an index of $s^{m - t} - \lambda$. The runs of the complement are in
+    /// lexicographic order.
+    ///
+    /// This returns an error if the array has duplicate runs or entries that are out of the range
+    /// of levels, if the array is already the full factorial design, or if the full factorial
+    /// design is too large to enumerate. It also returns an error if the number of runs in the
+    /// complement isn't a multiple of `levels^strength`, which means that the array wasn't an
+    /// orthogonal array of its strength.
+    pub fn complement(&self) -> OarsResult<OA<T>> {
+        let factors = as_usize(self.factors)?;
+        let overflow = || {
+            OarsError::InvalidParams(
+                "The full factorial design is too large to enumerate".to_owned(),
+            )
+        };
+        let full_runs = as_usize(checked_pow(self.levels, factors).ok_or_else(overflow)?)?;
+        let levels = as_usize(self.levels)?;
+        check_levels(&self.points, self.levels)?;
+
+        // Encode each run as a number in base `levels`, with the first factor as the most
+        // significant digit, so that enumerating the codes yields runs in lexicographic order
+        let mut present = HashSet::with_capacity(self.points.nrows());
+
+        for row in self.points.rows() {
+            let code = row
+                .iter()
+                .fold(0, |code, &x| code * levels + x.to_usize().unwrap());
+
+            if !present.insert(code) {
+                return Err(OarsError::InvalidParams(
+                    "The array has duplicate runs, so it is not a fraction of the full factorial"
+                        .to_owned(),
+                ));
+            }
+        }
+
+        if present.len() == full_runs {
+            return Err(OarsError::InvalidParams(
+                "The array is already the full factorial design".to_owned(),
+            ));
+        }
+        let mut entries = Vec::with_capacity((full_runs - present.len()) * factors);
+
+        for code in (0..full_runs).filter(|code| !present.contains(code)) {
+            let start = entries.len();
+            let mut rest = code;
+
+            for _ in 0..factors {
+                entries.push(from_usize_checked(rest % levels)?);
+                rest /= levels;
+            }
+            entries[start..].reverse();
+        }
+        // The index is computed from the runs of the complement rather than from the stored
+        // index of this array, which the caller could have set to anything
+        let runs = full_runs - present.len();
+
+        Ok(OA {
+            levels: self.levels,
+            strength: self.strength,
+            factors: self.factors,
+            index: compute_index(runs, self.levels, self.strength)?,
+            points: Array2::from_shape_vec((runs, factors), entries)?,
+        })
+    }
+
+    /// Append columns to the orthogonal array until it has `target` factors, while keeping it a
+    /// valid orthogonal array with the same strength.
+    ///
+    /// Each new column is a random linear combination of the existing columns, modulo the number
+    /// of levels, and it is only kept if every combination of `strength` columns that includes it
+    /// is still balanced. This reliably finds new columns for linear arrays with a prime number of
+    /// levels, such as the ones from the Bose and Bush constructions.
+    ///
+    /// This returns an error if `target` is less than the current number of factors, if `target`
+    /// exceeds the Bush bound on the number of factors for an array with an index of 1, or if no
+    /// column that preserves balance can be found.
+    pub fn pad_factors(&self, target: T, rng: &mut impl Rng) -> OarsResult<OA<T>> {
+        /// The maximum number of linear combinations to try for each new column
+        const MAX_CANDIDATES: usize = 4096;
+
+        let factors = as_usize(self.factors)?;
+        let target_usize = as_usize(target)?;
+        let strength = as_usize(self.strength)?;
+        let levels = as_usize(self.levels)?;
+
+        if target_usize < factors {
+            return Err(OarsError::InvalidParams(
+                "`target` must not be less than the current number of factors".to_owned(),
+            ));
+        }
+
+        // Bush showed that an array with an index of 1 and a strength of t >= 2 has at most t + 1
+        // factors if s <= t, at most s + t - 2 factors if s is odd and s > t >= 3, and at most
+        // s + t - 1 factors otherwise. There is no bound for a strength of 1.
+        let bound = if strength < 2 {
+            None
+        } else if levels <= strength {
+            Some(strength + 1)
+        } else if levels % 2 == 1 && strength >= 3 {
+            Some(levels + strength - 2)
+        } else {
+            Some(levels + strength - 1)
+        };
+
+        match bound {
+            Some(bound) if self.index == T::from(1).unwrap() && target_usize > bound => {
+                return Err(OarsError::InvalidParams(format!(
+                    "An array with {} levels and strength {} supports at most {} factors",
+                    levels, strength, bound
+                )))
+            }
+            _ => (),
+        }
+        let mut oa = OA {
+            levels: self.levels,
+            strength: self.strength,
+            factors: self.factors,
+            index: self.index,
+            points: self.points.clone(),
+        };
+
+        while oa.points.ncols() < target_usize {
+            let cols = oa.points.ncols();
+            let mut found = false;
+
+            for _ in 0..MAX_CANDIDATES {
+                let coeffs: Vec<T> = (0..cols)
+                    .map(|_| from_usize_checked(rng.gen_range(0..levels)))
+                    .collect::<OarsResult<_>>()?;
+
+                if coeffs.iter().all(|&a| a == T::from(0).unwrap()) {
+                    continue;
+                }
+                let column = oa.points.map_axis(Axis(1), |row| {
+                    row.iter()
+                        .zip(coeffs.iter())
+                        .fold(T::from(0).unwrap(), |acc, (&x, &a)| {
+                            (acc + a * x) % oa.levels
+                        })
+                });
+                let candidate = OA {
+                    points: concatenate(
+                        Axis(1),
+                        &[oa.points.view(), column.insert_axis(Axis(1)).view()],
+                    )?,
+                    factors: from_usize_checked(cols + 1)?,
+                    ..oa
+                };
+
+                // Only the combinations that include the new column could have become unbalanced
+                let balanced =
+                    (0..cols)
+                        .combinations(strength.saturating_sub(1))
+                        .all(|mut selection| {
+                            selection.push(cols);
+                            is_balanced(&candidate, &selection)
+                        });
+
+                if balanced {
+                    oa = candidate;
+                    found = true;
+                    break;
+                }
+            }
+
+            if !found {
+                return Err(OarsError::RuntimeError(
+                    "Could not find a new column that keeps the array balanced".to_owned(),
+                ));
+            }
+        }
+        Ok(oa)
+    }
+
+    /// Select `m` random runs from the orthogonal array without replacement.
+    ///
+    /// The selected runs keep all of their columns, and they are returned in the order they were
+    /// drawn. The result is generally not a valid orthogonal array, which is why only the points
+    /// are returned. This returns an error if `m` is greater than the number of runs.
+    pub fn subsample_runs(&self, m: usize, rng: &mut impl Rng) -> OarsResult<Array2<T>> {
+        let runs = self.points.nrows();
+
+        if m > runs {
+            return Err(OarsError::InvalidParams(format!(
+                "Can't select {} runs from an array with {} runs",
+                m, runs
+            )));
+        }
+        let selection = rand::seq::index::sample(rng, runs, m).into_vec();
+        Ok(self.points.select(Axis(0), &selection))
+    }
+
+    /// Map the integer levels of each run to user supplied labels.
+    ///
+    /// The entries of the array are codes between 0 and `levels`, rather than the actual values of
+    /// each factor. `labels[j][x]` is the label for level `x` of column `j`. If only one set of
+    /// labels is supplied, it is used for every column. The result has a row of labels for each run
+    /// of the array.
+    ///
+    /// This returns an error if there isn't one set of labels or a set of labels for each column,
+    /// or if an entry doesn't have a label.
+    pub fn decode_with(&self, labels: &[Vec<String>]) -> OarsResult<Vec<Vec<String>>> {
+        let cols = self.points.ncols();
+
+        if labels.len() != 1 && labels.len() != cols {
+            return Err(OarsError::InvalidParams(format!(
+                "Expected 1 or {} sets of labels, but got {}",
+                cols,
+                labels.len()
+            )));
+        }
+
+        self.points
+            .rows()
+            .into_iter()
+            .enumerate()
+            .map(|(i, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(|(j, &x)| {
+                        let column_labels = &labels[if labels.len() == 1 { 0 } else { j }];
+                        x.to_usize()
+                            .and_then(|x| column_labels.get(x))
+                            .cloned()
+                            .ok_or_else(|| {
+                                OarsError::InvalidParams(format!(
+                                    "The entry at ({}, {}) doesn't have a label",
+                                    i, j
+                                ))
+                            })
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Create the foldover design of the orthogonal array, which appends the mirror image of every
+    /// run.
+    ///
+    /// Each appended run replaces every entry $x$ of the original run with $s - 1 - x$. Mirroring
+    /// the levels doesn't affect balance, so the result is an orthogonal array with the same
+    /// strength, twice as many runs, and twice the index. This is mainly meaningful for two-level
+    /// arrays, where the foldover often raises the strength of the design, but the strength in
+    /// the metadata is left untouched.
+    ///
+    /// This returns an error if an entry is outside of the range of levels.
+    pub fn foldover(&self) -> OarsResult<OA<T>> {
+        check_levels(&self.points, self.levels)?;
+        let top = self.levels - T::from(1).unwrap();
+        let mirrored = self.points.mapv(|x| top - x);
+
+        Ok(OA {
+            levels: self.levels,
+            strength: self.strength,
+            factors: self.factors,
+            index: self.index + self.index,
+            points: concatenate(Axis(0), &[self.points.view(), mirrored.view()])?,
+        })
+    }
+
+    /// Iterate over the projection of every run onto the selected columns.
+    ///
+    /// Each item is the tuple formed by the entries of a run in `cols`, in the order that the
+    /// columns are listed. This panics if a column in `cols` is out of bounds.
+    pub fn iter_projection<'a>(&'a self, cols: &'a [usize]) -> impl Iterator<Item = Vec<T>> + 'a {
+        self.points
+            .rows()
+            .into_iter()
+            .map(move |row| cols.iter().map(|&j| row[j]).collect())
+    }
+
+    /// Create an orthogonal array from a list of runs, and verify that it is valid.
+    ///
+    /// Every run must have the same number of factors. The number of levels is inferred as the
+    /// largest entry plus one, and the index is inferred from the number of runs. This returns an
+    /// error if the runs have different lengths, or if they are not a valid orthogonal array with
+    /// the given strength.
+    pub fn from_rows(rows: Vec<Vec<T>>, strength: T) -> OarsResult<OA<T>> {
+        let factors = rows.first().map_or(0, Vec::len);
+
+        if rows.iter().any(|row| row.len() != factors) {
+            return Err(OarsError::InvalidParams(
+                "Every row must have the same number of factors".to_owned(),
+            ));
+        }
+        let runs = rows.len();
+        let points = Array2::from_shape_vec((runs, factors), rows.into_iter().flatten().collect())?;
+        from_points_verified(points, strength)
+    }
+
+    /// Partition the runs of the orthogonal array into `num_blocks` blocks of the same size, where
+    /// every block is balanced on every factor.
+    ///
+    /// In a balanced block, every level appears the same number of times in every column, so each
+    /// block can be run as a self-contained part of a blocked experiment. Each block is a list of
+    /// the indices of its runs, in ascending order. The partition is found with a backtracking
+    /// search, which can take exponential time for large arrays, so the search gives up after
+    /// visiting `RESOLVABLE_BLOCKS_SEARCH_LIMIT` partial assignments. Use
+    /// `resolvable_blocks_with_limit` to choose another limit.
+    ///
+    /// This returns an error if the number of runs isn't a multiple of `num_blocks * levels`, if
+    /// an entry is outside of the range of levels, if no such partition exists, or if the search
+    /// limit is reached.
+    pub fn resolvable_blocks(&self, num_blocks: usize) -> OarsResult<Vec<Vec<usize>>> {
+        self.resolvable_blocks_with_limit(num_blocks, RESOLVABLE_BLOCKS_SEARCH_LIMIT)
+    }
+
+    /// Partition the runs of the orthogonal array into `num_blocks` balanced blocks, visiting at
+    /// most `max_nodes` partial assignments during the search.
+    ///
+    /// This is the same as `resolvable_blocks`, and returns a `RuntimeError` if the search visits
+    /// more than `max_nodes` partial assignments without finding a partition.
+    pub fn resolvable_blocks_with_limit(
+        &self,
+        num_blocks: usize,
+        max_nodes: usize,
+    ) -> OarsResult<Vec<Vec<usize>>> {
+        let (runs, factors) = self.points.dim();
+        let levels = as_usize(self.levels)?;
+
+        match num_blocks.checked_mul(levels) {
+            Some(cells) if cells > 0 && runs % cells == 0 => (),
+            _ => {
+                return Err(OarsError::InvalidParams(
+                    "The number of runs must be a multiple of `num_blocks * levels`".to_owned(),
+                ))
+            }
+        }
+        let entries = level_indices(&self.points, self.levels)?;
+        let mut search = BlockSearch {
+            entries: &entries,
+            levels,
+            block_size: runs / num_blocks,
+            per_level: runs / num_blocks / levels,
+            counts: vec![vec![0; factors * levels]; num_blocks],
+            blocks: vec![Vec::new(); num_blocks],
+            budget: max_nodes,
+        };
+
+        if search.assign(0)? {
+            Ok(search.blocks)
+        } else {
+            Err(OarsError::InvalidParams(format!(
+                "The runs can't be partitioned into {} balanced blocks",
+                num_blocks
+            )))
+        }
+    }
+}
+
+/// The state of the backtracking search for `OA::resolvable_blocks`
+struct BlockSearch<'a> {
+    /// The levels of every run
+    entries: &'a [Vec<usize>],
+
+    /// The number of levels in the array
+    levels: usize,
+
+    /// The number of runs in every block
+    block_size: usize,
+
+    /// The number of times every level has to appear in every column of a block
+    per_level: usize,
+
+    /// The number of times each level appears in each column of each block, where the count for
+    /// level `l` of factor `j` is at index `j * levels + l`
+    counts: Vec<Vec<usize>>,
+
+    /// The runs that have been assigned to each block
+    blocks: Vec<Vec<usize>>,
+
+    /// The number of partial assignments that the search can still visit
+    budget: usize,
+}
+
+impl BlockSearch<'_> {
+    /// Assign every run from `run` onwards to a block, returning whether the assignment succeeded,
+    /// or a `RuntimeError` if the search runs out of budget
+    fn assign(&mut self, run: usize) -> OarsResult<bool> {
+        if run == self.entries.len() {
+            return Ok(true);
+        }
+
+        if self.budget == 0 {
+            return Err(OarsError::RuntimeError(
+                "The search for balanced blocks reached its limit".to_owned(),
+            ));
+        }
+        self.budget -= 1;
+        let levels = self.levels;
+
+        for b in 0..self.blocks.len() {
+            let fits = self.blocks[b].len() < self.block_size
+                && self.entries[run]
+                    .iter()
+                    .enumerate()
+                    .all(|(j, &l)| self.counts[b][j * levels + l] < self.per_level);
+
+            if fits {
+                self.set(run, b, true);
+
+                if self.assign(run + 1)? {
+                    return Ok(true);
+                }
+                self.set(run, b, false);
+            }
+
+            // Every empty block is interchangeable, so only the first one needs to be tried
+            if self.blocks[b].is_empty() {
+                break;
+            }
+        }
+        Ok(false)
+    }
+
+    /// Add a run to a block, or remove the run from the block if `add` is false
+    fn set(&mut self, run: usize, block: usize, add: bool) {
+        for (j, &l) in self.entries[run].iter().enumerate() {
+            let count = &mut self.counts[block][j * self.levels + l];
+
+            if add {
+                *count += 1;
+            } else {
+                *count -= 1;
+            }
+        }
+
+        if add {
+            self.blocks[block].push(run);
+        } else {
+            self.blocks[block].pop();
+        }
+    }
+}
+
+impl<T: Integer + Hash> OA<T> {
+    /// Build an index over the runs of the orthogonal array, so that many runs can be looked up
+    /// without a linear scan for each one.
+    pub fn build_index(&self) -> OaIndex<T> {
+        let mut runs = HashMap::with_capacity(self.points.nrows());
+
+        for (i, row) in self.points.rows().into_iter().enumerate() {
+            runs.entry(row.to_vec()).or_insert(i);
+        }
+        OaIndex { runs }
+    }
+}
+
+/// An index that maps each run of an orthogonal array to its position, built with
+/// `OA::build_index`.
+#[derive(Debug, Clone)]
+pub struct OaIndex<T: Integer + Hash> {
+    /// A mapping from a run to the first row where the run appears in the array
+    runs: HashMap<Vec<T>, usize>,
+}
+
+impl<T: Integer + Hash> OaIndex<T> {
+    /// Get the index of the first row that matches `run`, if it is in the array
+    pub fn lookup(&self, run: &[T]) -> Option<usize> {
+        self.runs.get(run).copied()
+    }
+}
+
+/// Search for a subset of `needed` rows such that no tuple is repeated in any combination of
+/// columns.
+///
+/// `tuples` holds the precomputed tuple index of each row for every column combination, and `seen`
+/// marks the tuples that are already used by the rows in `selected`. Since there are exactly
+/// `needed` possible tuples for each column combination, a subset of `needed` rows without any
+/// repeated tuples is balanced with an index of 1.
+///
+/// Every call decrements `budget`, and the search returns a `RuntimeError` once it runs out.
+fn balanced_subset(
+    tuples: &[Vec<usize>],
+    candidates: &[usize],
+    needed: usize,
+    seen: &mut [Vec<bool>],
+    selected: &mut Vec<usize>,
+    budget: &mut usize,
+) -> OarsResult<bool> {
+    if selected.len() == needed {
+        return Ok(true);
+    }
+
+    if *budget == 0 {
+        return Err(OarsError::RuntimeError(
+            "The search for a balanced subset reached its limit".to_owned(),
+        ));
+    }
+    *budget -= 1;
+
+    for (i, &row) in candidates.iter().enumerate() {
+        // There aren't enough candidates left to fill the subset
+        if candidates.len() - i + selected.len() < needed {
+            return Ok(false);
+        }
+        let fits = tuples[row]
+            .iter()
+            .zip(seen.iter())
+            .all(|(&tuple, combo_seen)| !combo_seen[tuple]);
+
+        if !fits {
+            continue;
+        }
+
+        for (&tuple, combo_seen) in tuples[row].iter().zip(seen.iter_mut()) {
+            combo_seen[tuple] = true;
+        }
+        selected.push(row);
+
+        if balanced_subset(tuples, &candidates[i + 1..], needed, seen, selected, budget)? {
+            return Ok(true);
+        }
+
+        // Backtrack if this row didn't lead to a balanced subset
+        for (&tuple, combo_seen) in tuples[row].iter().zip(seen.iter_mut()) {
+            combo_seen[tuple] = false;
+        }
+        selected.pop();
+    }
+    Ok(false)
+}
+
 /// A result type for orthogonal array construction.
 ///
 /// This `Result` type is specifically meant for OA constructors, which return some type of an
@@ -75,6 +941,11 @@ pub type OAResult<T> = Result<OA<T>, OarsError>;
 /// This method takes a regular orthogonal array, and converts it into a point set in the $[0, 1)^m$
 /// domain, so that it can be used as a sampling point set for Monte Carlo integration.
 ///
+/// Each entry is divided by the number of levels $s$, so level $i$ of a column maps to the strata
+/// $[i / s, (i + 1) / s)$. Earlier versions divided by the strength of the array instead, which
+/// only matches arrays with as many levels as their strength, and mapped the points of every other
+/// array (such as a Bose array with more than 2 levels) outside of $[0, 1)$.
+///
 /// _Note: it is unknown whether this method will work with construction techniques other than Bose
 /// and Bush construction._
 ///
@@ -87,173 +958,2367 @@ pub fn normalize<T: Integer, U: Float>(
     jitter: U,
     randomize: bool,
 ) -> OarsResult<Array2<U>> {
-    if oa.points.ndim() != 2 {
-        return Err(OarsError::InvalidParams(
-            "The `points` array in `oa` must be two dimensional".to_owned(),
-        ));
-    }
+    normalize_with_rng(oa, jitter, randomize, &mut rand::thread_rng())
+}
 
-    if jitter.to_f64().unwrap() < 0.0 || jitter.to_f64().unwrap() > 1.0 {
-        return Err(OarsError::InvalidParams(
-            "`jitter` must be between 0 and 1".to_owned(),
-        ));
-    }
+/// Normalize an orthogonal array into a point set, using the random number generator described by
+/// `config`.
+///
+/// This is the same as `normalize`, except that the output is reproducible if `config` has a seed.
+pub fn normalize_with_config<T: Integer, U: Float>(
+    oa: &OA<T>,
+    jitter: U,
+    randomize: bool,
+    config: &RngConfig,
+) -> OarsResult<Array2<U>> {
+    normalize_with_rng(oa, jitter, randomize, &mut config.rng())
+}
 
-    let dims = oa.points.shape();
-    let mut point_set = Array2::<U>::zeros((dims[0], dims[1]));
+/// Normalize an orthogonal array into a point set, using the supplied random number generator for
+/// the shuffling and jittering.
+///
+/// This is the same as `normalize`, but the caller controls the source of randomness, so a
+/// seeded generator yields a deterministic point set.
+pub fn normalize_with_rng<T: Integer, U: Float>(
+    oa: &OA<T>,
+    jitter: U,
+    randomize: bool,
+    rng: &mut impl Rng,
+) -> OarsResult<Array2<U>> {
+    normalize_with_placement(oa, jitter, randomize, Placement::Corner, rng)
+}
 
-    let mut perms: Vec<PermutationVector> = Vec::new();
-    let mut rng = rand::thread_rng();
+/// Where the points are placed within each strata when normalizing an orthogonal array
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Placement {
+    /// Jitter upwards from the lower corner of each strata, so a jitter of 0 places each point
+    /// at the lower corner
+    #[default]
+    Corner,
 
-    // Create the permutation vectors. If "randomize" is requested, apply the
-    // shuffle. Otherwise, it will be an identity vector, and applying it will
-    // not result in any randomization.
-    for i in 0..dims[1] {
-        perms.push(PermutationVector::new(dims[0]));
+    /// Center the jitter range within each strata, so a jitter of 0 places each point at the
+    /// midpoint of its strata
+    Centered,
+}
 
-        if randomize {
-            perms[i].shuffle();
-        }
-    }
+/// Normalize an orthogonal array into a point set, placing the points within each strata as
+/// described by `placement`.
+///
+/// This is the same as `normalize_with_rng` if `placement` is `Placement::Corner`.
+pub fn normalize_with_placement<T: Integer, U: Float>(
+    oa: &OA<T>,
+    jitter: U,
+    randomize: bool,
+    placement: Placement,
+    rng: &mut impl Rng,
+) -> OarsResult<Array2<U>> {
+    check_normalize_params(oa, jitter)?;
+    let dims = oa.points.shape();
+    let mut point_set = Array2::<U>::zeros((dims[0], dims[1]));
+    let perms = column_permutations(dims[0], dims[1], randomize, rng);
+    let offset = match placement {
+        Placement::Corner => U::from(0).unwrap(),
+        Placement::Centered => (U::from(1).unwrap() - jitter) / U::from(2).unwrap(),
+    };
+    let levels = U::from(oa.levels).unwrap();
 
     // loop through each point in the OA and convert to a point in the pointset
     // note: `genrows()` does not seem to implement `enumerate()` so we need the explicit loop
     // counter.
     //for row in oa.points.genrows() {
     for i in 0..dims[0] {
-        for j in 0..dims[1] {
+        for (j, perm) in perms.iter().enumerate() {
             // Apply the shuffle with the permutation vector to get the new index for the
             // point
-            let shuffled_i = perms[j][i];
+            let shuffled_i = perm[i];
 
             // Apply jitter factor (random number between 0 and jitter as an upper bound)
-            // If jitter is 0, then the points will be centered in the strata.
-            let jittered_point: U =
-                U::from(oa.points[[i, j]]).unwrap() + (jitter * U::from(rng.gen::<f64>()).unwrap());
-            point_set[[shuffled_i, j]] = jittered_point / U::from(oa.strength).unwrap();
+            // If jitter is 0, then the points will be placed at the offset in the strata.
+            let level = U::from(oa.points[[i, j]]).unwrap();
+            let jittered_point: U = level + offset + (jitter * U::from(rng.gen::<f64>()).unwrap());
+            point_set[[shuffled_i, j]] = clamp_to_strata(jittered_point, level, levels);
         }
     }
     Ok(point_set)
 }
 
-/// Given some orthogonal array struct, verify that the points are a valid orthogonal array as
-/// described by the parameters.
+/// Normalize an orthogonal array into a point set, shuffling the points within each strata.
 ///
-/// An orthogonal array is defined by four key parameters, and this function attempts to see
-/// if `points` matches up with the other parameters. This means that for any (and every)
-/// selection of $t$ columns, every possible combination of $t$-tuples must be present in that
-/// submatrix. You can easily map the combinations in a unique way using base $s$ where $s$ is
-/// the number of factors in the array (assuming it is a symmetrical array).
-pub fn verify<T: Integer>(oa: &OA<T>) -> OarsResult<bool>
-where
-{
-    if oa.points.ndim() != 2 {
-        return Err(OarsError::InvalidParams(
-            "`oa.points` must be two-dimensional".to_owned(),
-        ));
-    }
-
-    if oa.points.shape()[1] != oa.factors.to_usize().unwrap() {
-        return Ok(false);
-    }
-
-    let col_combos =
-        (0..oa.factors.to_u64().unwrap()).combinations(oa.strength.to_usize().unwrap());
-
-    // this iterator gives us every possible combination of columns
-    for selection in col_combos {
-        // tuple count holds the count for how many times each possible tuple is seen
-        let mut tuple_count: HashMap<u64, u64> = HashMap::new();
+/// For every column, the runs are grouped by their level. A group of $k$ runs at some level
+/// evenly divides the strata for that level into $k$ smaller cells, and each run in the group is
+/// assigned its own cell with an independent shuffle, then jittered uniformly within that cell.
+/// Every point still falls within the strata of its level, and the points in every column also
+/// form a Latin hypercube sample, as described by Tang in "Orthogonal Array-Based Latin
+/// Hypercubes" (1993).
+///
+/// This returns an error if any entry of the array is outside of the range of levels.
+pub fn normalize_nested<T: Integer, U: Float>(
+    oa: &OA<T>,
+    rng: &mut impl Rng,
+) -> OarsResult<Array2<U>> {
+    check_normalize_params(oa, U::from(1).unwrap())?;
+    check_levels(&oa.points, oa.levels)?;
+    let levels = as_usize(oa.levels)?;
+    let mut point_set = Array2::<U>::zeros(oa.points.dim());
 
-        // loop through the points and count up how many times we encounter the tuple
-        for i in 0..oa.points.shape()[0] {
-            let mut tuple_index = 0;
+    for (col, mut out_col) in oa.points.columns().into_iter().zip(point_set.columns_mut()) {
+        let mut groups: Vec<Vec<usize>> = vec![Vec::new(); levels];
 
-            for (power, column) in selection.iter().enumerate() {
-                tuple_index += (oa.points[[i, column.to_usize().unwrap()]] * pow(oa.levels, power))
-                    .to_u64()
-                    .unwrap();
-            }
-            // set count to 1 if it doesn't exist, otherwise update the count
-            *tuple_count.entry(tuple_index).or_insert(0) += 1;
+        for (i, &x) in col.iter().enumerate() {
+            groups[x.to_usize().unwrap()].push(i);
         }
 
-        // now verify that the hashmap has every possible combination, `index` times
-        for i in 0..oa
-            .levels
-            .to_u64()
-            .unwrap()
-            .pow(oa.strength.to_u32().unwrap())
-        {
-            // if the entry is not present in the array, set the count to 0
-            if *tuple_count.entry(i).or_insert(0) != oa.index.to_u64().unwrap() {
-                return Ok(false);
+        for (level, group) in groups.iter().enumerate() {
+            let mut perm = PermutationVector::new(group.len());
+            perm.shuffle(rng);
+
+            for (rank, &i) in group.iter().enumerate() {
+                let cell = (perm[rank] as f64 + rng.gen::<f64>()) / group.len() as f64;
+                out_col[i] = U::from((level as f64 + cell) / levels as f64).unwrap();
             }
         }
     }
-    Ok(true)
+    Ok(point_set)
 }
 
-/// A generic trait to demarcate orthogonal array constructors
-pub trait OAConstructor<T: Integer> {
-    /// Generate an orthogonal array
-    fn gen(&self) -> OAResult<T>;
+/// Normalize an orthogonal array into a point set, and check that the output is well-formed.
+///
+/// This is the same as `normalize`, with a final pass that ensures every point is finite and lies
+/// in $[0, 1)$. This returns a `RuntimeError` if it doesn't, which can catch subtle bugs in custom
+/// `Float` implementations.
+pub fn normalize_checked<T: Integer, U: Float>(
+    oa: &OA<T>,
+    jitter: U,
+    randomize: bool,
+) -> OarsResult<Array2<U>> {
+    let point_set = normalize(oa, jitter, randomize)?;
+    check_unit_points(&point_set)?;
+    Ok(point_set)
+}
+
+/// Check that every point in a point set is finite and lies in $[0, 1)$
+fn check_unit_points<U: Float>(point_set: &Array2<U>) -> OarsResult<()> {
+    let zero = U::from(0).unwrap();
+    let one = U::from(1).unwrap();
+
+    match point_set
+        .indexed_iter()
+        .find(|(_, &x)| !x.is_finite() || x < zero || x >= one)
+    {
+        Some(((i, j), x)) => Err(OarsError::RuntimeError(format!(
+            "The point at ({}, {}) is {}, which is not in [0, 1)",
+            i,
+            j,
+            x.to_f64().unwrap_or(f64::NAN)
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Normalize an orthogonal array into a point set, processing the columns in parallel.
+///
+/// This is the same as `normalize`, but it is meant for large orthogonal arrays. The permutation
+/// vectors are created serially, then each column is jittered and placed in parallel, using a
+/// separate random number generator for each thread.
+#[cfg(feature = "parallel")]
+pub fn normalize_par<T: Integer, U: Float>(
+    oa: &OA<T>,
+    jitter: U,
+    randomize: bool,
+) -> OarsResult<Array2<U>> {
+    check_normalize_params(oa, jitter)?;
+    let dims = oa.points.shape();
+    let mut point_set = Array2::<U>::zeros((dims[0], dims[1]));
+    let perms = column_permutations(dims[0], dims[1], randomize, &mut rand::thread_rng());
+    let levels = U::from(oa.levels).unwrap();
+
+    point_set
+        .axis_iter_mut(Axis(1))
+        .into_par_iter()
+        .zip(oa.points.axis_iter(Axis(1)).into_par_iter())
+        .zip(perms.par_iter())
+        .for_each(|((mut col, oa_col), perm)| {
+            let mut rng = rand::thread_rng();
+
+            for (i, &x) in oa_col.iter().enumerate() {
+                let level = U::from(x).unwrap();
+                let jittered_point: U = level + (jitter * U::from(rng.gen::<f64>()).unwrap());
+                col[perm[i]] = clamp_to_strata(jittered_point, level, levels);
+            }
+        });
+    Ok(point_set)
+}
+
+/// Scale a jittered point down to the unit interval, keeping it strictly below the upper boundary
+/// of its strata.
+///
+/// With a jitter of 1, the point can land on the upper boundary of the strata because of
+/// floating point rounding, which would place it in the next strata (or at 1 for the last one).
+/// Such points are moved to just below the boundary.
+pub(crate) fn clamp_to_strata<U: Float>(jittered_point: U, level: U, levels: U) -> U {
+    let point = jittered_point / levels;
+    let upper = (level + U::from(1).unwrap()) / levels;
+
+    if point < upper {
+        point
+    } else {
+        // `upper * epsilon` is at least one ulp of `upper`, so this is strictly below `upper`
+        upper - upper * U::epsilon()
+    }
+}
+
+/// Check that the parameters for normalizing an orthogonal array are valid
+fn check_normalize_params<T: Integer, U: Float>(oa: &OA<T>, jitter: U) -> OarsResult<()> {
+    if oa.points.ndim() != 2 {
+        return Err(OarsError::InvalidParams(
+            "The `points` array in `oa` must be two dimensional".to_owned(),
+        ));
+    }
+
+    if jitter.to_f64().unwrap() < 0.0 || jitter.to_f64().unwrap() > 1.0 {
+        return Err(OarsError::InvalidParams(
+            "`jitter` must be between 0 and 1".to_owned(),
+        ));
+    }
+    Ok(())
+}
+
+/// Create a permutation vector for each column of a point set.
+///
+/// If `randomize` is requested, each permutation is shuffled. Otherwise, each one will be an
+/// identity vector, and applying it will not result in any randomization.
+pub(crate) fn column_permutations(
+    rows: usize,
+    cols: usize,
+    randomize: bool,
+    rng: &mut impl Rng,
+) -> Vec<PermutationVector> {
+    (0..cols)
+        .map(|_| {
+            let mut perm = PermutationVector::new(rows);
+
+            if randomize {
+                perm.shuffle(rng);
+            }
+            perm
+        })
+        .collect()
+}
+
+/// Given some orthogonal array struct, verify that the points are a valid orthogonal array as
+/// described by the parameters.
+///
+/// An orthogonal array is defined by four key parameters, and this function attempts to see
+/// if `points` matches up with the other parameters. This means that for any (and every)
+/// selection of $t$ columns, every possible combination of $t$-tuples must be present in that
+/// submatrix. You can easily map the combinations in a unique way using base $s$ where $s$ is
+/// the number of factors in the array (assuming it is a symmetrical array).
+pub fn verify<T: Integer>(oa: &OA<T>) -> OarsResult<bool>
+where
+{
+    if oa.points.ndim() != 2 {
+        return Err(OarsError::InvalidParams(
+            "`oa.points` must be two-dimensional".to_owned(),
+        ));
+    }
+
+    if oa.points.shape()[1] != oa.factors.to_usize().unwrap() {
+        return Ok(false);
+    }
+
+    let col_combos =
+        (0..oa.factors.to_usize().unwrap()).combinations(oa.strength.to_usize().unwrap());
+
+    // this iterator gives us every possible combination of columns
+    for selection in col_combos {
+        if !is_balanced(oa, &selection) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Verify that the points of an orthogonal array are balanced with the supplied index.
+///
+/// This is the same as `verify`, except that every tuple must appear exactly `expected_index`
+/// times instead of `oa.index` times, which is useful if the stored index might be wrong.
+pub fn verify_with_index<T: Integer>(oa: &OA<T>, expected_index: u64) -> bool {
+    if oa.factors.to_usize() != Some(oa.points.ncols()) {
+        return false;
+    }
+
+    (0..oa.points.ncols())
+        .combinations(oa.strength.to_usize().unwrap())
+        .all(|selection| is_balanced_with_index(oa, &selection, expected_index))
+}
+
+/// Verify an orthogonal array by checking a random sample of its column combinations.
+///
+/// This checks `combos` randomly chosen combinations of `strength` columns in the same way as
+/// `verify`, which is much cheaper for large arrays. This is probabilistic: it never rejects a
+/// valid orthogonal array, but it may accept an invalid one if none of the unbalanced
+/// combinations of columns are sampled.
+pub fn verify_sampled<T: Integer>(oa: &OA<T>, combos: usize, rng: &mut impl Rng) -> bool {
+    let factors = oa.points.ncols();
+    let strength = oa.strength.to_usize().unwrap();
+
+    if oa.factors.to_usize() != Some(factors) {
+        return false;
+    }
+
+    if strength > factors {
+        return true;
+    }
+
+    (0..combos).all(|_| {
+        let mut selection = rand::seq::index::sample(rng, factors, strength).into_vec();
+        selection.sort_unstable();
+        is_balanced(oa, &selection)
+    })
+}
+
+/// Verify an orthogonal array by checking a random sample of its column combinations in parallel.
+///
+/// This is the same as `verify_sampled`, but the sampled combinations are checked across
+/// threads. A seed is drawn for each sample from the random number generator described by
+/// `config`, so the samples, and the result, are reproducible if `config` has a seed, no matter
+/// how the work is scheduled.
+#[cfg(feature = "parallel")]
+pub fn verify_sampled_par<T: Integer>(oa: &OA<T>, combos: usize, config: &RngConfig) -> bool {
+    let factors = oa.points.ncols();
+    let strength = oa.strength.to_usize().unwrap();
+
+    if oa.factors.to_usize() != Some(factors) {
+        return false;
+    }
+
+    if strength > factors {
+        return true;
+    }
+    let mut master = config.rng();
+    let seeds: Vec<u64> = (0..combos).map(|_| master.gen()).collect();
+
+    seeds.into_par_iter().all(|seed| {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut selection = rand::seq::index::sample(&mut rng, factors, strength).into_vec();
+        selection.sort_unstable();
+        is_balanced(oa, &selection)
+    })
+}
+
+/// Check that an orthogonal array is valid, returning an error that describes the array if it
+/// isn't.
+///
+/// This is meant for crates that implement their own `OAConstructor` and want to reuse the
+/// verification in their tests.
+#[cfg(feature = "test-utils")]
+pub fn check_valid<T: Integer>(oa: &OA<T>) -> OarsResult<()> {
+    if verify(oa)? {
+        return Ok(());
+    }
+    let describe = format_integer::<T>;
+
+    Err(OarsError::RuntimeError(format!(
+        "The array with {} runs, {} factors, {} levels, strength {}, and index {} is not a valid \
+         orthogonal array",
+        oa.points.nrows(),
+        describe(oa.factors),
+        describe(oa.levels),
+        describe(oa.strength),
+        describe(oa.index)
+    )))
+}
+
+/// Assert that an orthogonal array is valid.
+///
+/// This panics with a message describing the array if `check_valid` fails, so it can be used as a
+/// drop-in test assertion.
+#[cfg(feature = "test-utils")]
+pub fn assert_valid<T: Integer>(oa: &OA<T>) {
+    if let Err(e) = check_valid(oa) {
+        panic!("{}", e);
+    }
+}
+
+/// Check whether every possible tuple appears exactly `index` times in the submatrix of an
+/// orthogonal array that consists of the selected columns.
+fn is_balanced<T: Integer>(oa: &OA<T>, selection: &[usize]) -> bool {
+    is_balanced_with_index(oa, selection, oa.index.to_u64().unwrap())
+}
+
+/// Check whether every possible tuple appears exactly `index` times in the submatrix of an
+/// orthogonal array that consists of the selected columns, using the supplied index instead of
+/// the index of the array.
+fn is_balanced_with_index<T: Integer>(oa: &OA<T>, selection: &[usize], index: u64) -> bool {
+    let expected = oa.expected_tuple_count();
+
+    // Every possible tuple has to appear at least once, which can't happen if there are more
+    // possible tuples than runs. This also avoids a long loop if the tuple count saturated.
+    if expected > oa.points.nrows() as u64 {
+        return false;
+    }
+
+    // tuple count holds the count for how many times each possible tuple is seen
+    let mut tuple_count: HashMap<u64, u64> = HashMap::new();
+
+    // loop through the points and count up how many times we encounter the tuple
+    for i in 0..oa.points.shape()[0] {
+        // set count to 1 if it doesn't exist, otherwise update the count
+        *tuple_count
+            .entry(tuple_index(oa, i, selection))
+            .or_insert(0) += 1;
+    }
+
+    // now verify that the hashmap has every possible combination, `index` times, which means
+    // that it has exactly `expected` distinct tuples that are all in range
+    tuple_count.len() as u64 == expected
+        && tuple_count
+            .iter()
+            .all(|(&tuple, &count)| tuple < expected && count == index)
+}
+
+/// Map the tuple formed by the selected columns of a row to a unique index, by treating the tuple
+/// as a number in base `levels`.
+fn tuple_index<T: Integer>(oa: &OA<T>, row: usize, selection: &[usize]) -> u64 {
+    selection
+        .iter()
+        .enumerate()
+        .map(|(power, &column)| {
+            (oa.points[[row, column]] * pow(oa.levels, power))
+                .to_u64()
+                .unwrap()
+        })
+        .sum()
+}
+
+/// Find the runs of an orthogonal array whose removal would most improve its balance.
+///
+/// For every combination of `strength` columns, removing a run brings the submatrix closer to
+/// balance if the tuple of that run appears more than `index` times, and further from balance
+/// otherwise. This scores each run by how many combinations of columns its removal would improve,
+/// less the number it would worsen, and returns the runs with the highest positive score in
+/// ascending order. A valid orthogonal array has no such runs.
+///
+/// This is a heuristic to help clean up arrays that are close to valid. Note that if a run was
+/// corrupted into a duplicate of another run, both runs receive the same score, so both are
+/// returned.
+pub fn find_corrupt_rows<T: Integer>(oa: &OA<T>) -> Vec<usize> {
+    let runs = oa.points.nrows();
+    let index = oa.index.to_u64().unwrap();
+    let mut scores = vec![0i64; runs];
+
+    for selection in (0..oa.points.ncols()).combinations(oa.strength.to_usize().unwrap()) {
+        let tuples: Vec<u64> = (0..runs).map(|i| tuple_index(oa, i, &selection)).collect();
+        let mut tuple_count: HashMap<u64, u64> = HashMap::new();
+
+        for &tuple in &tuples {
+            *tuple_count.entry(tuple).or_insert(0) += 1;
+        }
+
+        for (score, tuple) in scores.iter_mut().zip(tuples.iter()) {
+            *score += if tuple_count[tuple] > index { 1 } else { -1 };
+        }
+    }
+
+    match scores.iter().max() {
+        Some(&best) if best > 0 => (0..runs).filter(|&i| scores[i] == best).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// List every combination of `strength` columns in an orthogonal array, and whether each
+/// combination is balanced.
+///
+/// A combination of columns is balanced if every possible tuple appears exactly `index` times in
+/// the submatrix made up of those columns. This exposes the per-combination results that `verify`
+/// computes as structured data, which is useful for reports and for finding which columns break
+/// the orthogonal array guarantee.
+pub fn balance_table<T: Integer>(oa: &OA<T>) -> Vec<(Vec<usize>, bool)> {
+    (0..oa.points.ncols())
+        .combinations(oa.strength.to_usize().unwrap())
+        .map(|selection| {
+            let balanced = is_balanced(oa, &selection);
+            (selection, balanced)
+        })
+        .collect()
+}
+
+/// Compute the fraction of possible tuples that are covered by an orthogonal array.
+///
+/// For every combination of `strength` columns, this counts the distinct tuples that appear in
+/// the submatrix made up of those columns, and divides that by the number of possible tuples,
+/// $s^t$. The result is the average of that fraction over every combination of columns. A valid
+/// orthogonal array scores 1, and entries that are outside of the range of levels are never
+/// counted. This returns 0 if the array has fewer factors than its strength.
+pub fn coverage_ratio<T: Integer>(oa: &OA<T>) -> f64 {
+    let possible = oa.expected_tuple_count() as f64;
+    let zero = T::from(0).unwrap();
+    let mut total = 0.0;
+    let mut combos = 0;
+
+    for selection in (0..oa.points.ncols()).combinations(oa.strength.to_usize().unwrap()) {
+        let observed: HashSet<u64> = oa
+            .points
+            .rows()
+            .into_iter()
+            .filter(|row| {
+                selection
+                    .iter()
+                    .all(|&j| row[j] >= zero && row[j] < oa.levels)
+            })
+            .map(|row| {
+                selection
+                    .iter()
+                    .enumerate()
+                    .map(|(power, &j)| (row[j] * pow(oa.levels, power)).to_u64().unwrap())
+                    .sum()
+            })
+            .collect();
+        total += observed.len() as f64 / possible;
+        combos += 1;
+    }
+
+    if combos == 0 {
+        return 0.0;
+    }
+    total / f64::from(combos)
+}
+
+/// Check whether two columns of an orthogonal array are fully aliased.
+///
+/// Two columns are aliased if the level of one of them is a deterministic function of the level
+/// of the other, in which case the effects of the two factors can't be told apart. A column is
+/// always aliased with itself.
+///
+/// This panics if `a` or `b` is out of bounds.
+pub fn are_aliased<T: Integer>(oa: &OA<T>, a: usize, b: usize) -> bool {
+    // Sorting the pairs of levels groups every level of `from` together, so `from` determines `to`
+    // if every group has a single level of `to`
+    let determines = |from: usize, to: usize| {
+        let mut pairs: Vec<(T, T)> = oa
+            .points
+            .rows()
+            .into_iter()
+            .map(|row| (row[from], row[to]))
+            .collect();
+        pairs.sort_unstable();
+        pairs
+            .windows(2)
+            .all(|pair| pair[0].0 != pair[1].0 || pair[0].1 == pair[1].1)
+    };
+    determines(a, b) || determines(b, a)
+}
+
+/// Compute the Pearson correlation between every pair of columns in a point set.
+///
+/// Entry $(i, j)$ of the resulting matrix is the correlation between columns $i$ and $j$, so the
+/// matrix is symmetric with ones along the diagonal. A point set derived from an orthogonal array
+/// should have off-diagonal entries that are close to zero. Any entry that involves a column with
+/// zero variance is NaN.
+pub fn correlation_matrix<U: Float>(points: &Array2<U>) -> Array2<U> {
+    let cols = points.ncols();
+    let zero = U::from(0).unwrap();
+    let rows = U::from(points.nrows()).unwrap();
+    let mut centered = points.clone();
+
+    for mut col in centered.columns_mut() {
+        let mean = col.iter().fold(zero, |acc, &x| acc + x) / rows;
+        col.mapv_inplace(|x| x - mean);
+    }
+    let norms: Vec<U> = centered
+        .columns()
+        .into_iter()
+        .map(|col| col.iter().fold(zero, |acc, &x| acc + x * x).sqrt())
+        .collect();
+
+    Array2::from_shape_fn((cols, cols), |(i, j)| {
+        let dot = centered
+            .column(i)
+            .iter()
+            .zip(centered.column(j).iter())
+            .fold(zero, |acc, (&x, &y)| acc + x * y);
+        dot / (norms[i] * norms[j])
+    })
+}
+
+/// Expand every factor of an orthogonal array into indicator (dummy) columns, which yields a design
+/// matrix for fitting linear models.
+///
+/// Every factor is expanded into `levels - 1` columns, one for each level besides the reference
+/// level 0, which is dropped so the columns aren't collinear with an intercept. The column for
+/// level $l$ of factor $j$ is at index $j (s - 1) + l - 1$, and it is 1 for every run where factor
+/// $j$ is at level $l$, and 0 otherwise. Runs at the reference level, or at a level outside of the
+/// range of levels, are 0 in every column for that factor.
+pub fn to_indicator_matrix<T: Integer>(oa: &OA<T>) -> Array2<f64> {
+    let (runs, factors) = oa.points.dim();
+    let width = oa.levels.to_usize().unwrap_or(0).saturating_sub(1);
+    let mut design = Array2::<f64>::zeros((runs, factors * width));
+
+    for ((row, factor), &x) in oa.points.indexed_iter() {
+        match x.to_usize() {
+            Some(level) if level >= 1 && level <= width => {
+                design[[row, factor * width + level - 1]] = 1.0
+            }
+            _ => (),
+        }
+    }
+    design
+}
+
+/// Check whether a 2-level orthogonal array is a regular fraction, which means that its runs form
+/// a linear subspace over $\mathbb{Z}_2$.
+///
+/// The distinct runs of the array must be closed under elementwise XOR, so they form a subgroup,
+/// and every distinct run has to appear the same number of times. This returns false if the
+/// array doesn't have 2 levels, or if it has an entry besides 0 and 1.
+pub fn is_regular_fraction<T: Integer>(oa: &OA<T>) -> bool {
+    let (zero, one) = (T::from(0).unwrap(), T::from(1).unwrap());
+
+    if oa.levels != T::from(2).unwrap() || oa.points.iter().any(|&x| x != zero && x != one) {
+        return false;
+    }
+    let mut counts: HashMap<Vec<bool>, usize> = HashMap::new();
+
+    for row in oa.points.rows() {
+        *counts
+            .entry(row.iter().map(|&x| x == one).collect())
+            .or_default() += 1;
+    }
+
+    if counts.values().collect::<HashSet<_>>().len() > 1 {
+        return false;
+    }
+    counts.keys().tuple_combinations().all(|(a, b)| {
+        let sum: Vec<bool> = a.iter().zip(b.iter()).map(|(&x, &y)| x ^ y).collect();
+        counts.contains_key(&sum)
+    }) && (counts.is_empty() || counts.contains_key(&vec![false; oa.points.ncols()]))
+}
+
+/// Compute the Rao bound, which is a lower bound for the number of runs in an orthogonal array
+/// with the given number of levels, strength, and factors.
+///
+/// For a strength of $t = 2u$, the bound is $\sum_{i=0}^{u} \binom{k}{i} (s - 1)^i$. For a
+/// strength of $t = 2u + 1$, the bound has an additional term of
+/// $\binom{k - 1}{u} (s - 1)^{u + 1}$.
+/// This saturates at `u64::MAX` instead of overflowing.
+pub fn rao_bound(levels: u64, strength: u64, factors: u64) -> u64 {
+    // Compute binom(n, i) * (s - 1)^power, saturating at `u64::MAX`
+    let term = |n: u64, i: u64, power: u64| -> u64 {
+        if i > n {
+            return 0;
+        }
+        // The running product stays exact, since the product of j + 1 consecutive integers is
+        // divisible by (j + 1)!
+        let binomial = (0..i).try_fold(1u128, |acc, j| {
+            acc.checked_mul(u128::from(n - j))
+                .map(|x| x / u128::from(j + 1))
+        });
+        let power = u32::try_from(power).unwrap_or(u32::MAX);
+        binomial
+            .zip(u128::from(levels.saturating_sub(1)).checked_pow(power))
+            .and_then(|(b, p)| b.checked_mul(p))
+            .map_or(u64::MAX, |x| u64::try_from(x).unwrap_or(u64::MAX))
+    };
+    let u = strength / 2;
+    let bound = (0..=u).fold(0u64, |acc, i| acc.saturating_add(term(factors, i, i)));
+
+    if strength % 2 == 1 {
+        bound.saturating_add(term(factors.saturating_sub(1), u, u + 1))
+    } else {
+        bound
+    }
+}
+
+/// Find the combination of `k` columns of an orthogonal array whose projection is the most
+/// uniform.
+///
+/// The array is normalized with every point at the center of its strata, and each projection
+/// onto `k` columns is scored by its centered $L_2$ discrepancy. This returns the columns of the
+/// projection with the lowest discrepancy, in ascending order, along with its discrepancy. Ties
+/// are broken in favor of the combination that comes first lexicographically. Every combination
+/// of columns is evaluated, so this can be slow for arrays with many factors.
+///
+/// This returns an error if `k` is 0 or greater than the number of factors.
+pub fn best_projection<T: Integer>(oa: &OA<T>, k: usize) -> OarsResult<(Vec<usize>, f64)> {
+    if k == 0 || k > oa.points.ncols() {
+        return Err(OarsError::InvalidParams(
+            "`k` must be between 1 and the number of factors".to_owned(),
+        ));
+    }
+    // The points aren't jittered or shuffled, so they don't depend on the generator, and a fixed
+    // seed avoids setting up a thread-local generator for every call
+    let mut rng = StdRng::seed_from_u64(0);
+    let points: Array2<f64> =
+        normalize_with_placement(oa, 0.0, false, Placement::Centered, &mut rng)?;
+    let mut best: Option<(Vec<usize>, f64)> = None;
+
+    for selection in (0..points.ncols()).combinations(k) {
+        let score = centered_l2_discrepancy(&points.select(Axis(1), &selection));
+
+        let improves = match &best {
+            Some((_, best_score)) => score < *best_score,
+            None => true,
+        };
+
+        if improves {
+            best = Some((selection, score));
+        }
+    }
+    Ok(best.unwrap())
+}
+
+/// Find the words of the defining relation of a two-level orthogonal array.
+///
+/// A word is a set of columns whose elementwise XOR is constant across every run, which means
+/// that the interaction between those factors is confounded with the mean. This returns every
+/// word of the defining relation, not just its generators, with the columns of each word in
+/// ascending order. The words are sorted by length, then lexicographically. Every subset of the
+/// columns is checked, so this takes time that is exponential in the number of factors.
+///
+/// This returns an empty vector if the array doesn't have two levels.
+pub fn defining_relation<T: Integer>(oa: &OA<T>) -> Vec<Vec<usize>> {
+    if oa.levels != T::from(2).unwrap() {
+        return Vec::new();
+    }
+    let one = T::from(1).unwrap();
+    let mut words: Vec<Vec<usize>> = (1..=oa.points.ncols())
+        .flat_map(|len| (0..oa.points.ncols()).combinations(len))
+        .filter(|word| {
+            let xor = |row: ArrayView1<T>| word.iter().filter(|&&j| row[j] == one).count() % 2;
+            let mut rows = oa.points.rows().into_iter();
+
+            match rows.next() {
+                Some(first) => rows.all(|row| xor(row) == xor(first)),
+                None => false,
+            }
+        })
+        .collect();
+    words.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+    words
+}
+
+/// Combine two orthogonal arrays with the same number of levels into a larger orthogonal array,
+/// using their Kronecker sum.
+///
+/// If `a` has $N_1$ runs and $k_1$ factors and `b` has $N_2$ runs and $k_2$ factors, the result
+/// has $N_1 N_2$ runs and $k_1 k_2$ factors. Run $r N_2 + q$ and factor $i k_2 + j$ of the result
+/// is `(a[r, i] + b[q, j]) % levels`. Both arrays must have the same number of levels $s$. The
+/// result is an orthogonal array with a strength of 2 if both arrays have a strength of at least
+/// 2, or a strength of 1 otherwise, since higher strengths aren't preserved in general. Its index
+/// is $N_1 N_2 / s^t$.
+///
+/// This returns an error if the arrays have a different number of levels, if either array has an
+/// entry outside of the range of levels, or if the number of runs or factors of the result
+/// overflows.
+pub fn kronecker<T: Integer>(a: &OA<T>, b: &OA<T>) -> OarsResult<OA<T>> {
+    if a.levels != b.levels {
+        return Err(OarsError::InvalidParams(
+            "Both arrays must have the same number of levels".to_owned(),
+        ));
+    }
+    check_levels(&a.points, a.levels)?;
+    check_levels(&b.points, b.levels)?;
+    let two = T::from(2).unwrap();
+    let strength = if a.strength >= two && b.strength >= two {
+        two
+    } else {
+        T::from(1).unwrap()
+    };
+    let (runs_a, factors_a) = a.points.dim();
+    let (runs_b, factors_b) = b.points.dim();
+    let overflow = || OarsError::InvalidParams("The combined array is too large".to_owned());
+    let runs = runs_a.checked_mul(runs_b).ok_or_else(overflow)?;
+    let factors = factors_a.checked_mul(factors_b).ok_or_else(overflow)?;
+
+    Ok(OA {
+        levels: a.levels,
+        strength,
+        factors: from_usize_checked(factors)?,
+        index: compute_index(runs, a.levels, strength)?,
+        points: Array2::from_shape_fn((runs, factors), |(row, col)| {
+            let (r, q) = (row / runs_b, row % runs_b);
+            let (i, j) = (col / factors_b, col % factors_b);
+            (a.points[[r, i]] + b.points[[q, j]]) % a.levels
+        }),
+    })
+}
+
+/// Compute how unevenly the levels appear in each column of an orthogonal array.
+///
+/// For every column, this counts how many times each of the `levels` levels appears, and returns
+/// the population variance of those counts. A perfectly balanced column has an imbalance of 0.
+/// Entries that are outside of the range of levels aren't counted, so they also increase the
+/// imbalance of their column.
+pub fn column_imbalance<T: Integer>(oa: &OA<T>) -> Vec<f64> {
+    let levels = oa.levels.to_usize().unwrap_or(0);
+
+    if levels == 0 {
+        return vec![0.0; oa.points.ncols()];
+    }
+
+    oa.points
+        .columns()
+        .into_iter()
+        .map(|col| {
+            let mut counts = vec![0usize; levels];
+
+            for level in col.iter().filter_map(|x| x.to_usize()) {
+                if let Some(count) = counts.get_mut(level) {
+                    *count += 1;
+                }
+            }
+            let mean = counts.iter().sum::<usize>() as f64 / levels as f64;
+            counts
+                .iter()
+                .map(|&count| (count as f64 - mean).powi(2))
+                .sum::<f64>()
+                / levels as f64
+        })
+        .collect()
+}
+
+/// Find every pair of identical rows in an orthogonal array.
+///
+/// An orthogonal array with an index of 1 should not have any duplicate rows, so finding
+/// duplicates is a cheap sanity check for construction bugs that is independent of `verify`. Each
+/// pair of row indices `(i, j)` satisfies `i < j`, and the pairs are returned in sorted order.
+pub fn duplicate_rows<T: Integer>(oa: &OA<T>) -> Vec<(usize, usize)> {
+    // Sorting the row indices by the contents of each row puts all of the identical rows next to
+    // each other. The sort is stable, so the indices of identical rows stay in ascending order.
+    let order = sorted_row_order(&oa.points);
+
+    let mut pairs: Vec<(usize, usize)> = order
+        .iter()
+        .group_by(|&&i| oa.points.row(i))
+        .into_iter()
+        .flat_map(|(_, group)| {
+            group
+                .copied()
+                .collect::<Vec<usize>>()
+                .into_iter()
+                .tuple_combinations()
+        })
+        .collect();
+    pairs.sort_unstable();
+    pairs
+}
+
+/// Compute the index of an orthogonal array from its number of runs, which is
+/// `runs / levels^strength`.
+///
+/// Constructors use this instead of hardcoding the index, so the stored index always matches the
+/// generated points. This returns an error if the number of runs is not a multiple of
+/// `levels^strength`.
+pub(crate) fn compute_index<T: Integer>(runs: usize, levels: T, strength: T) -> OarsResult<T> {
+    let tuples = checked_pow(levels, as_usize(strength)?)
+        .filter(|&tuples| tuples > T::from(0).unwrap())
+        .ok_or_else(|| OarsError::InvalidParams("`levels^strength` is out of range".to_owned()))?;
+    let runs: T = from_usize_checked(runs)?;
+
+    if runs % tuples != T::from(0).unwrap() {
+        return Err(OarsError::InvalidParams(
+            "The number of runs is not a multiple of `levels^strength`".to_owned(),
+        ));
+    }
+    Ok(runs / tuples)
+}
+
+/// Check that every entry of an array is in the range of levels, returning an error that names
+/// the first run with an entry that isn't.
+pub(crate) fn check_levels<T: Integer>(points: &Array2<T>, levels: T) -> OarsResult<()> {
+    let zero = T::from(0).unwrap();
+
+    // The entries are visited in row-major order, so the position of an entry determines its run
+    match points.iter().position(|&x| x < zero || x >= levels) {
+        Some(i) => Err(OarsError::InvalidParams(format!(
+            "Row {} has an entry outside of the range of levels",
+            i / points.ncols()
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Convert every run of an array to the indices of its levels, after checking that every entry
+/// is in the range of levels with `check_levels`.
+fn level_indices<T: Integer>(points: &Array2<T>, levels: T) -> OarsResult<Vec<Vec<usize>>> {
+    check_levels(points, levels)?;
+    Ok(points
+        .rows()
+        .into_iter()
+        .map(|row| row.iter().map(|x| x.to_usize().unwrap()).collect())
+        .collect())
+}
+
+/// Return the orthogonal array if it passes `verify`, or an error otherwise.
+///
+/// This is used by the strict variants of the checked constructors to make sure that the output
+/// of the constructor is valid.
+pub(crate) fn ensure_valid<T: Integer>(oa: OA<T>) -> OAResult<T> {
+    if !verify(&oa)? {
+        return Err(OarsError::RuntimeError(
+            "The constructor generated an invalid orthogonal array".to_owned(),
+        ));
+    }
+    Ok(oa)
+}
+
+/// Create an orthogonal array from raw points, inferring the number of levels and the index from
+/// the data, and verify that the result is a valid orthogonal array.
+///
+/// The number of levels is the largest entry plus one, and the index is the number of runs
+/// divided by `levels^strength`. This returns an error if the points can't be an orthogonal array
+/// with the given strength.
+pub(crate) fn from_points_verified<T: Integer>(points: Array2<T>, strength: T) -> OAResult<T> {
+    let zero = T::from(0).unwrap();
+
+    if points.is_empty() {
+        return Err(OarsError::InvalidParams(
+            "The array must have at least one run and one factor".to_owned(),
+        ));
+    }
+
+    if points.iter().any(|&x| x < zero) {
+        return Err(OarsError::InvalidParams(
+            "The entries of the array can't be negative".to_owned(),
+        ));
+    }
+
+    let factors: T = from_usize_checked(points.ncols())?;
+
+    if strength < T::from(1).unwrap() || strength > factors {
+        return Err(OarsError::InvalidParams(
+            "`strength` must be between 1 and the number of factors (inclusive)".to_owned(),
+        ));
+    }
+    let max = as_usize(*points.iter().max().unwrap())?;
+    let levels: T = from_usize_checked(max.checked_add(1).ok_or_else(|| {
+        OarsError::InvalidParams("The number of levels is out of range".to_owned())
+    })?)?;
+    let index = compute_index(points.nrows(), levels, strength)?;
+
+    let oa = OA {
+        levels,
+        strength,
+        factors,
+        index,
+        points,
+    };
+
+    if !verify(&oa)? {
+        return Err(OarsError::InvalidParams(
+            "The points are not a valid orthogonal array".to_owned(),
+        ));
+    }
+    Ok(oa)
+}
+
+/// Get the indices of the rows of an array, sorted lexicographically by the contents of each row.
+///
+/// The sort is stable, so identical rows stay in the same relative order.
+fn sorted_row_order<T: Integer>(points: &Array2<T>) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..points.nrows()).collect();
+    order.sort_by(|&a, &b| points.row(a).iter().cmp(points.row(b).iter()));
+    order
+}
+
+/// A generic trait to demarcate orthogonal array constructors
+pub trait OAConstructor<T: Integer> {
+    /// Generate an orthogonal array
+    fn gen(&self) -> OAResult<T>;
+
+    /// Get a human-readable description of the constructor and the parameters of the orthogonal
+    /// array it generates, without generating it.
+    ///
+    /// The default implementation only reports the name of the constructor type.
+    fn describe(&self) -> String {
+        std::any::type_name::<Self>().to_owned()
+    }
 }
 
 /// A generic trait that demarcates a parallelized orthogonal array constructor.
 pub trait ParOAConstructor<T: Integer> {
     /// Generate an orthogonal array with parallel construction
     fn gen_par(&self) -> OAResult<T>;
+
+    /// Generate an orthogonal array with parallel construction, using at most `n` threads
+    ///
+    /// This runs `gen_par` inside of a dedicated thread pool instead of rayon's global thread
+    /// pool, which bounds the number of threads used by each construction. If `n` is 0, rayon
+    /// picks the number of threads.
+    #[cfg(feature = "parallel")]
+    fn gen_par_with_threads(&self, n: usize) -> OAResult<T>
+    where
+        Self: Sync,
+    {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(n)
+            .build()
+            .map_err(|e| OarsError::RuntimeError(e.to_string()))?;
+        pool.install(|| self.gen_par())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ndarray::arr2;
+    use crate::constructors::{Bose, Bush};
+    use ndarray::{arr2, aview1, s};
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn test_verify_oa_bad_in() {
+        let points = arr2(&[
+            [0, 0, 0],
+            [0, 1, 1],
+            [0, 2, 2],
+            [1, 0, 0],
+            [1, 0, 0],
+            [1, 2, 2],
+            [2, 0, 0],
+            [2, 1, 1],
+            [2, 2, 2],
+        ]);
+        let oa = OA {
+            strength: 3,
+            levels: 3,
+            index: 1,
+            factors: 3,
+            points,
+        };
+        assert!(!verify(&oa).unwrap());
+    }
+
+    #[test]
+    fn test_verify_oa_good_in() {
+        let points = arr2(&[
+            [0, 0, 0],
+            [0, 1, 1],
+            [0, 2, 2],
+            [1, 0, 1],
+            [1, 1, 2],
+            [1, 2, 0],
+            [2, 0, 2],
+            [2, 1, 0],
+            [2, 2, 1],
+        ]);
+        let oa = OA {
+            strength: 2,
+            levels: 3,
+            index: 1,
+            factors: 3,
+            points,
+        };
+        assert!(verify(&oa).unwrap());
+    }
+
+    #[test]
+    fn test_duplicate_rows() {
+        let points = arr2(&[
+            [0, 0, 0],
+            [0, 1, 1],
+            [0, 2, 2],
+            [1, 0, 0],
+            [1, 0, 0],
+            [1, 2, 2],
+            [2, 0, 0],
+            [2, 1, 1],
+            [2, 2, 2],
+        ]);
+        let oa = OA {
+            strength: 3,
+            levels: 3,
+            index: 1,
+            factors: 3,
+            points,
+        };
+        assert!(duplicate_rows(&oa) == vec![(3, 4)]);
+
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        let oa = bose.gen().unwrap();
+        assert!(duplicate_rows(&oa).is_empty());
+    }
+
+    #[test]
+    fn test_normalize_scales_by_levels() {
+        // A Bose array has a strength of 2, so with 5 levels the points only lie in [0, 1) if they
+        // are scaled by the number of levels
+        let bose = Bose {
+            prime_base: 5,
+            dimensions: 4,
+        };
+        let oa = bose.gen().unwrap();
+        let points: Array2<f64> = normalize(&oa, 0.0, false).unwrap();
+        assert!(points == oa.points.mapv(|x| f64::from(x) / 5.0));
+
+        let points: Array2<f64> = normalize(&oa, 0.5, true).unwrap();
+        assert!(points.iter().all(|&x| (0.0..1.0).contains(&x)));
+
+        #[cfg(feature = "parallel")]
+        {
+            let points: Array2<f64> = normalize_par(&oa, 0.5, true).unwrap();
+            assert!(points.iter().all(|&x| (0.0..1.0).contains(&x)));
+        }
+    }
+
+    #[test]
+    fn test_normalize_with_config_reproducible() {
+        let bose = Bose {
+            prime_base: 5,
+            dimensions: 4,
+        };
+        let bose_oa = bose.gen().unwrap();
+        let bush = Bush {
+            prime_base: 3,
+            strength: 3,
+            dimensions: 3,
+        };
+        let bush_oa = bush.gen().unwrap();
+        let config = RngConfig::seeded(42);
+
+        let run = || -> (Array2<f64>, Array2<f64>) {
+            (
+                normalize_with_config(&bose_oa, 0.5, true, &config).unwrap(),
+                normalize_with_config(&bush_oa, 1.0, true, &config).unwrap(),
+            )
+        };
+        assert!(run() == run());
+
+        let mut rng = config.rng();
+        let first: Array2<f64> = normalize_with_rng(&bose_oa, 0.5, true, &mut rng).unwrap();
+        let second: Array2<f64> = normalize_with_rng(&bose_oa, 0.5, true, &mut rng).unwrap();
+        assert!(first != second);
+    }
+
+    #[test]
+    fn test_append_constant_column() {
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        let mut oa = bose.gen().unwrap();
+        let original = oa.points.clone();
+        oa.append_constant_column(7);
+
+        assert!(oa.factors == 4);
+        assert!(oa.points.ncols() == 4);
+        assert!(oa.points.column(3).iter().all(|&x| x == 7));
+        assert!(oa.points.slice(s![.., ..3]) == original);
+    }
+
+    #[test]
+    fn test_reduce_strength_bush() {
+        let bush = Bush {
+            prime_base: 3,
+            strength: 3,
+            dimensions: 3,
+        };
+        let oa = bush.gen().unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+        let reduced = oa.reduce_strength(2, &mut rng).unwrap();
+        assert!(reduced.points.nrows() == 9);
+        assert!(reduced.strength == 2);
+        assert!(verify(&reduced).unwrap());
+
+        let bush = Bush {
+            prime_base: 5,
+            strength: 3,
+            dimensions: 5,
+        };
+        let oa = bush.gen().unwrap();
+        let reduced = oa.reduce_strength(2, &mut rng).unwrap();
+        assert!(reduced.points.nrows() == 25);
+        assert!(verify(&reduced).unwrap());
+    }
+
+    #[test]
+    fn test_reduce_strength_bad_target() {
+        let bush = Bush {
+            prime_base: 3,
+            strength: 2,
+            dimensions: 3,
+        };
+        let oa = bush.gen().unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+        assert!(oa.reduce_strength(0, &mut rng).is_err());
+        assert!(oa.reduce_strength(3, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_reduce_strength_limit() {
+        let bush = Bush {
+            prime_base: 3,
+            strength: 3,
+            dimensions: 3,
+        };
+        let oa = bush.gen().unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        // The search has to visit at least as many partial subsets as the number of runs it picks
+        assert!(matches!(
+            oa.reduce_strength_with_limit(2, 8, &mut rng),
+            Err(OarsError::RuntimeError(_))
+        ));
+        assert!(oa.reduce_strength_with_limit(2, 1000, &mut rng).is_ok());
+    }
+
+    #[test]
+    fn test_reduce_strength_out_of_range() {
+        let bush = Bush {
+            prime_base: 3,
+            strength: 3,
+            dimensions: 3,
+        };
+        let mut oa = bush.gen().unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+        oa.points[[4, 1]] = 3;
+        assert!(matches!(
+            oa.reduce_strength(2, &mut rng),
+            Err(OarsError::InvalidParams(_))
+        ));
+    }
+
+    #[test]
+    fn test_check_levels() {
+        let mut points = arr2(&[[0, 1], [1, 0], [1, 1]]);
+        assert!(check_levels(&points, 2).is_ok());
+
+        points[[1, 0]] = -1;
+        points[[2, 1]] = 2;
+        match check_levels(&points, 2) {
+            Err(OarsError::InvalidParams(msg)) => assert!(msg.starts_with("Row 1 ")),
+            _ => panic!("expected an error for row 1"),
+        }
+    }
+
+    #[test]
+    fn test_map_levels() {
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        let oa = bose.gen().unwrap();
+        let mapped = oa.map_levels(|x| x - 1);
+        assert!(mapped.iter().all(|&x| (-1..=1).contains(&x)));
+        assert!(mapped.row(4) == aview1(&[0, 0, 1]));
+        assert!(mapped == oa.points.mapv(|x| x - 1));
+    }
+
+    #[test]
+    fn test_column() {
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        let oa = bose.gen().unwrap();
+        assert!(oa.column(1).unwrap() == vec![0, 1, 2, 0, 1, 2, 0, 1, 2]);
+        assert!(oa.column(0).unwrap() == vec![0, 0, 0, 1, 1, 1, 2, 2, 2]);
+        assert!(oa.column(3).is_err());
+
+        let columns: Vec<Vec<u32>> = oa.columns().collect();
+        assert!(columns.len() == 3);
+        assert!(columns[2] == vec![0, 1, 2, 1, 2, 0, 2, 0, 1]);
+    }
+
+    #[test]
+    fn test_balance_table() {
+        let bose = Bose {
+            prime_base: 5,
+            dimensions: 4,
+        };
+        let oa = bose.gen().unwrap();
+        let table = balance_table(&oa);
+        assert!(table.len() == 6);
+        assert!(table.iter().all(|(_, balanced)| *balanced));
+
+        let points = arr2(&[
+            [0, 0, 0],
+            [0, 1, 1],
+            [0, 2, 2],
+            [1, 0, 1],
+            [1, 1, 1],
+            [1, 2, 0],
+            [2, 0, 2],
+            [2, 1, 0],
+            [2, 2, 1],
+        ]);
+        let oa = OA {
+            strength: 2,
+            levels: 3,
+            index: 1,
+            factors: 3,
+            points,
+        };
+        let table = balance_table(&oa);
+        assert!(table == vec![(vec![0, 1], true), (vec![0, 2], false), (vec![1, 2], false)]);
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn test_normalize_par_matches_serial() {
+        let bose = Bose {
+            prime_base: 7,
+            dimensions: 5,
+        };
+        let oa = bose.gen().unwrap();
+        let serial: Array2<f64> = normalize(&oa, 0.0, false).unwrap();
+        let parallel: Array2<f64> = normalize_par(&oa, 0.0, false).unwrap();
+        assert!(serial == parallel);
+        assert!(normalize_par::<u32, f64>(&oa, 1.5, false).is_err());
+    }
+
+    #[test]
+    fn test_into_points() {
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        let oa = bose.gen().unwrap();
+        let expected = oa.points.clone();
+        assert!(oa.into_points() == expected);
+    }
+
+    #[test]
+    fn test_replicate() {
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        let oa = bose.gen().unwrap();
+        let replicated = oa.replicate(2).unwrap();
+        assert!(replicated.index == 2);
+        assert!(replicated.points.nrows() == 18);
+        assert!(replicated.points.slice(s![9.., ..]) == oa.points);
+        assert!(verify(&replicated).unwrap());
+
+        // The index of the result doesn't fit in a u8, and the number of runs overflows
+        let bose: Bose<u8> = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        let oa = bose.gen().unwrap();
+        assert!(oa.replicate(300).is_err());
+        assert!(oa.replicate(usize::MAX / 2).is_err());
+    }
+
+    #[test]
+    fn test_sample_flags() {
+        let bose = Bose {
+            prime_base: 5,
+            dimensions: 3,
+        };
+        let oa = bose.gen().unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for &randomize in &[false, true] {
+            for &verify_first in &[false, true] {
+                for &jitter in &[0.0, 0.5] {
+                    let points = oa
+                        .sample(jitter, randomize, verify_first, &mut rng)
+                        .unwrap();
+                    assert!(points.dim() == oa.points.dim());
+
+                    if !randomize && jitter == 0.0 {
+                        assert!(points == normalize(&oa, 0.0, false).unwrap());
+                    }
+                }
+            }
+        }
+        assert!(oa.sample(2.0, true, true, &mut rng).is_err());
+
+        let mut oa = oa;
+        oa.points[[0, 0]] = 1;
+        assert!(oa.sample(0.0, false, true, &mut rng).is_err());
+        assert!(oa.sample(0.0, false, false, &mut rng).is_ok());
+    }
+
+    #[test]
+    fn test_canonicalize() {
+        let bose = Bose {
+            prime_base: 5,
+            dimensions: 4,
+        };
+        let mut oa = bose.gen().unwrap();
+        let mut order: Vec<usize> = (0..oa.points.nrows()).collect();
+        order.shuffle(&mut StdRng::seed_from_u64(0));
+        let mut shuffled = OA {
+            points: oa.points.select(Axis(0), &order),
+            ..bose.gen().unwrap()
+        };
+        assert!(shuffled.points != oa.points);
+
+        shuffled.canonicalize();
+        oa.canonicalize();
+        assert!(shuffled.points == oa.points);
+        assert!(verify(&shuffled).unwrap());
+    }
+
+    #[test]
+    fn test_expected_runs() {
+        let bose = Bose {
+            prime_base: 5,
+            dimensions: 4,
+        };
+        let oa = bose.gen().unwrap();
+        assert!(oa.expected_tuple_count() == 25);
+        assert!(oa.expected_runs() == oa.points.nrows() as u64);
+        assert!(oa.replicate(3).unwrap().expected_runs() == 75);
+
+        let bush = Bush {
+            prime_base: 3,
+            strength: 3,
+            dimensions: 4,
+        };
+        let oa = bush.gen().unwrap();
+        assert!(oa.expected_tuple_count() == 27);
+        assert!(oa.expected_runs() == oa.points.nrows() as u64);
+
+        let oa = OA {
+            levels: 251_u64,
+            strength: 10,
+            factors: 2,
+            index: 1,
+            points: Array2::zeros((0, 2)),
+        };
+        assert!(oa.expected_tuple_count() == u64::MAX);
+    }
+
+    #[test]
+    fn test_vstack() {
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        let oa = bose.gen().unwrap();
+        let stacked = oa.vstack(&oa).unwrap();
+        assert!(stacked.index == 2);
+        assert!(stacked.points.nrows() == 18);
+        assert!(verify(&stacked).unwrap());
+
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 2,
+        };
+        assert!(oa.vstack(&bose.gen().unwrap()).is_err());
+
+        let mut corrupted = bose.gen().unwrap();
+        corrupted.points[[0, 0]] = 1;
+        let two_dims = bose.gen().unwrap();
+        assert!(two_dims.vstack(&corrupted).is_err());
+    }
+
+    #[test]
+    fn test_normalize_checked() {
+        let bose = Bose {
+            prime_base: 5,
+            dimensions: 4,
+        };
+        let oa = bose.gen().unwrap();
+        assert!(normalize_checked::<u32, f64>(&oa, 0.0, true).is_ok());
+        assert!(normalize_checked::<u32, f64>(&oa, 1.0, true).is_ok());
+
+        let points: Array2<f64> = normalize_checked(&oa, 0.0, false).unwrap();
+        assert!(points.iter().all(|&x| (0.0..1.0).contains(&x)));
+    }
+
+    #[test]
+    fn test_check_unit_points() {
+        assert!(check_unit_points(&arr2(&[[0.0, 0.5], [0.25, 0.75]])).is_ok());
+        assert!(check_unit_points(&arr2(&[[0.0, f64::NAN], [0.25, 0.75]])).is_err());
+        assert!(check_unit_points(&arr2(&[[0.0, f64::INFINITY], [0.25, 0.75]])).is_err());
+        assert!(check_unit_points(&arr2(&[[0.0, 1.0], [0.25, 0.75]])).is_err());
+        assert!(check_unit_points(&arr2(&[[-0.1, 0.5], [0.25, 0.75]])).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "test-utils")]
+    fn test_check_valid() {
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        let mut oa = bose.gen().unwrap();
+        assert_valid(&oa);
+        assert!(check_valid(&oa).is_ok());
+
+        oa.points[[0, 0]] = 1;
+        assert!(check_valid(&oa).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "test-utils")]
+    #[should_panic(expected = "is not a valid orthogonal array")]
+    fn test_assert_valid_corrupted() {
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        let mut oa = bose.gen().unwrap();
+        oa.points[[0, 0]] = 1;
+        assert_valid(&oa);
+    }
+
+    #[test]
+    fn test_coverage_ratio() {
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 4,
+        };
+        let mut oa = bose.gen().unwrap();
+        assert!(coverage_ratio(&oa) == 1.0);
+
+        // Make every run identical, so each pair of columns only covers one of the nine tuples
+        oa.points.fill(0);
+        assert!((coverage_ratio(&oa) - 1.0 / 9.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_normalize_with_placement() {
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        let oa = bose.gen().unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let centered: Array2<f64> =
+            normalize_with_placement(&oa, 0.0, false, Placement::Centered, &mut rng).unwrap();
+        assert!(centered == oa.points.mapv(|x| (f64::from(x) + 0.5) / 3.0));
+
+        let corner: Array2<f64> =
+            normalize_with_placement(&oa, 0.0, false, Placement::default(), &mut rng).unwrap();
+        assert!(corner == oa.points.mapv(|x| f64::from(x) / 3.0));
+
+        let jittered: Array2<f64> =
+            normalize_with_placement(&oa, 1.0, true, Placement::Centered, &mut rng).unwrap();
+        assert!(jittered.iter().all(|x| (0.0..1.0).contains(x)));
+    }
+
+    #[test]
+    fn test_are_aliased() {
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 4,
+        };
+        let mut oa = bose.gen().unwrap();
+
+        for (a, b) in (0..4).tuple_combinations() {
+            assert!(!are_aliased(&oa, a, b));
+        }
+        assert!(are_aliased(&oa, 2, 2));
+
+        // Make the last column a relabeling of the first one
+        let relabeled = oa.points.column(0).mapv(|x| (x + 1) % 3);
+        oa.points.column_mut(3).assign(&relabeled);
+        assert!(are_aliased(&oa, 0, 3));
+        assert!(are_aliased(&oa, 3, 0));
+        assert!(!are_aliased(&oa, 1, 3));
+
+        // Entries above `i128::MAX` can be compared too
+        let oa: OA<u128> = OA {
+            levels: u128::MAX,
+            strength: 1,
+            factors: 2,
+            index: 1,
+            points: arr2(&[[u128::MAX - 1, 0], [0, u128::MAX - 1]]),
+        };
+        assert!(are_aliased(&oa, 0, 1));
+    }
 
     #[test]
-    fn test_verify_oa_bad_in() {
-        let points = arr2(&[
-            [0, 0, 0],
-            [0, 1, 1],
-            [0, 2, 2],
-            [1, 0, 0],
-            [1, 0, 0],
-            [1, 2, 2],
-            [2, 0, 0],
-            [2, 1, 1],
-            [2, 2, 2],
-        ]);
-        let oa = OA {
-            strength: 3,
-            levels: 3,
-            index: 1,
-            factors: 3,
-            points,
+    fn test_normalize_nested() {
+        let bose = Bose {
+            prime_base: 5,
+            dimensions: 4,
         };
-        assert!(!verify(&oa).unwrap());
+        let oa = bose.gen().unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+        let points: Array2<f64> = normalize_nested(&oa, &mut rng).unwrap();
+
+        for (col, point_col) in oa.points.columns().into_iter().zip(points.columns()) {
+            for (&level, &x) in col.iter().zip(point_col.iter()) {
+                let level = f64::from(level);
+                assert!(level / 5.0 <= x && x < (level + 1.0) / 5.0);
+            }
+
+            // Each of the 25 points should fall in a different one of 25 equal intervals
+            let mut cells: Vec<usize> = point_col.iter().map(|x| (x * 25.0) as usize).collect();
+            cells.sort_unstable();
+            assert!(cells == (0..25).collect::<Vec<_>>());
+        }
+
+        let mut corrupted = oa;
+        corrupted.points[[0, 0]] = 5;
+        assert!(normalize_nested::<u32, f64>(&corrupted, &mut rng).is_err());
     }
 
     #[test]
-    fn test_verify_oa_good_in() {
+    fn test_find_corrupt_rows() {
+        // Row 4 was corrupted into a duplicate of row 3
         let points = arr2(&[
             [0, 0, 0],
             [0, 1, 1],
             [0, 2, 2],
             [1, 0, 1],
-            [1, 1, 2],
+            [1, 0, 1],
             [1, 2, 0],
             [2, 0, 2],
             [2, 1, 0],
             [2, 2, 1],
         ]);
-        let oa = OA {
+        let mut oa = OA {
             strength: 2,
             levels: 3,
             index: 1,
             factors: 3,
             points,
         };
-        assert!(verify(&oa).unwrap());
+        assert!(find_corrupt_rows(&oa) == vec![3, 4]);
+
+        oa.points.row_mut(4).assign(&aview1(&[1, 1, 2]));
+        assert!(find_corrupt_rows(&oa).is_empty());
+    }
+
+    #[test]
+    fn test_to_latex() {
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        let latex = bose.gen().unwrap().to_latex();
+        assert!(latex.contains("\\begin{tabular}{ccc}"));
+        assert!(latex.contains("\\caption{$OA(9, 3, 3, 2)$ with index 1}"));
+        assert!(latex.contains("1 & 2 & 0 \\\\\n"));
+        assert!(latex.matches("\\\\").count() == 9);
+
+        // Entries above `i128::MAX` are still formatted
+        let oa: OA<u128> = OA {
+            levels: u128::MAX,
+            strength: 1,
+            factors: 1,
+            index: 1,
+            points: arr2(&[[u128::MAX - 1]]),
+        };
+        assert!(oa.to_latex().contains(&format!("{} \\\\\n", u128::MAX - 1)));
+    }
+
+    #[test]
+    fn test_complement() {
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        let oa = bose.gen().unwrap();
+        let complement = oa.complement().unwrap();
+        assert!(complement.points.nrows() == 18);
+        assert!(complement.index == 2);
+        assert!(verify(&complement).unwrap());
+
+        // Together, the array and its complement should be the full factorial design
+        let mut full = OA {
+            points: concatenate(Axis(0), &[oa.points.view(), complement.points.view()]).unwrap(),
+            ..complement
+        };
+        full.canonicalize();
+        let expected: Vec<u32> = (0..27u32)
+            .flat_map(|code| vec![code / 9, (code / 3) % 3, code % 3])
+            .collect();
+        assert!(full.points == Array2::from_shape_vec((27, 3), expected).unwrap());
+
+        full.index = 3;
+        assert!(full.complement().is_err());
+        assert!(oa.vstack(&oa).unwrap().complement().is_err());
+
+        // The index of the complement doesn't depend on the stored index, even if it's wrong
+        let wrong_index = OA { index: 10, ..oa };
+        assert!(wrong_index.complement().unwrap().index == 2);
+    }
+
+    #[test]
+    fn test_build_index() {
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        let oa = bose.gen().unwrap();
+        let index = oa.build_index();
+
+        for (i, row) in oa.points.rows().into_iter().enumerate() {
+            assert!(index.lookup(&row.to_vec()) == Some(i));
+        }
+        assert!(index.lookup(&[1, 2, 0]) == Some(5));
+        assert!(index.lookup(&[0, 0, 1]).is_none());
+        assert!(index.lookup(&[0, 0]).is_none());
+        assert!(index.lookup(&[3, 0, 0]).is_none());
+    }
+
+    #[test]
+    fn test_correlation_matrix() {
+        let bose = Bose {
+            prime_base: 5,
+            dimensions: 4,
+        };
+        let oa = bose.gen().unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let points: Array2<f64> = normalize_with_rng(&oa, 0.0, false, &mut rng).unwrap();
+        let corr = correlation_matrix(&points);
+        assert!(corr.dim() == (4, 4));
+
+        for ((i, j), &r) in corr.indexed_iter() {
+            if i == j {
+                assert!((r - 1.0).abs() < 1e-12);
+            } else {
+                assert!(r.abs() < 1e-12);
+                assert!(r == corr[[j, i]]);
+            }
+        }
+
+        let points: Array2<f64> = normalize_with_rng(&oa, 1.0, false, &mut rng).unwrap();
+        let corr = correlation_matrix(&points);
+        assert!(corr
+            .indexed_iter()
+            .all(|((i, j), &r)| i == j || r.abs() < 0.1));
+
+        let constant = Array2::<f64>::zeros((4, 2));
+        assert!(correlation_matrix(&constant).iter().all(|x| x.is_nan()));
+    }
+
+    #[test]
+    fn test_pad_factors() {
+        let bose = Bose {
+            prime_base: 5,
+            dimensions: 3,
+        };
+        let oa = bose.gen().unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let padded = oa.pad_factors(4, &mut rng).unwrap();
+        assert!(padded.factors == 4);
+        assert!(padded.points.slice(s![.., ..3]) == oa.points);
+        assert!(verify(&padded).unwrap());
+
+        let padded = oa.pad_factors(6, &mut rng).unwrap();
+        assert!(verify(&padded).unwrap());
+
+        assert!(oa.pad_factors(7, &mut rng).is_err());
+        assert!(oa.pad_factors(2, &mut rng).is_err());
+
+        let bush = Bush {
+            prime_base: 3,
+            strength: 3,
+            dimensions: 3,
+        };
+        let padded = bush.gen().unwrap().pad_factors(4, &mut rng).unwrap();
+        assert!(verify(&padded).unwrap());
+
+        // An odd number of levels above the strength allows s + t - 2 factors, so 7 factors are
+        // impossible for 5 levels and strength 3, rather than hard to find
+        let bush = Bush {
+            prime_base: 5,
+            strength: 3,
+            dimensions: 4,
+        };
+        assert!(matches!(
+            bush.gen().unwrap().pad_factors(7, &mut rng),
+            Err(OarsError::InvalidParams(_))
+        ));
+
+        // There is no bound on the number of factors for a strength of 1
+        let bush = Bush {
+            prime_base: 3,
+            strength: 1,
+            dimensions: 2,
+        };
+        let padded = bush.gen().unwrap().pad_factors(6, &mut rng).unwrap();
+        assert!(verify(&padded).unwrap());
+    }
+
+    #[test]
+    fn test_verify_sampled() {
+        let bose = Bose {
+            prime_base: 11,
+            dimensions: 12,
+        };
+        let mut oa = bose.gen().unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+        assert!(verify_sampled(&oa, 10, &mut rng));
+
+        // Corrupting an entry unbalances every combination of columns that contains it, so a
+        // sample of combinations should almost always find one of them
+        oa.points[[0, 0]] = 1;
+        oa.points[[0, 5]] = 1;
+        oa.points[[0, 11]] = 1;
+        assert!(!verify_sampled(&oa, 50, &mut rng));
+    }
+
+    #[test]
+    fn test_oa_hash() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::Hasher;
+
+        let hash = |oa: &OA<u32>| {
+            let mut hasher = DefaultHasher::new();
+            oa.hash(&mut hasher);
+            hasher.finish()
+        };
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        let oa = bose.gen().unwrap();
+        let mut other = bose.gen().unwrap();
+        assert!(oa == other);
+        assert!(hash(&oa) == hash(&other));
+
+        other.points[[0, 0]] = 1;
+        assert!(oa != other);
+        assert!(hash(&oa) != hash(&other));
+
+        // Swapping two runs changes the hash, until the array is canonicalized
+        let mut swapped = bose.gen().unwrap();
+        swapped.points = swapped.points.select(Axis(0), &[1, 0, 2, 3, 4, 5, 6, 7, 8]);
+        assert!(hash(&oa) != hash(&swapped));
+        swapped.canonicalize();
+        assert!(hash(&oa) == hash(&swapped));
+    }
+
+    #[test]
+    fn test_rao_bound() {
+        // The Bose construction meets the bound when it has the maximum number of factors
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 4,
+        };
+        let oa = bose.gen().unwrap();
+        assert!(rao_bound(3, 2, 4) == 9);
+        assert!(rao_bound(3, 2, 4) <= oa.points.nrows() as u64);
+
+        let bose = Bose {
+            prime_base: 5,
+            dimensions: 3,
+        };
+        let oa = bose.gen().unwrap();
+        assert!(rao_bound(5, 2, 3) == 13);
+        assert!(rao_bound(5, 2, 3) <= oa.points.nrows() as u64);
+
+        let bush = Bush {
+            prime_base: 3,
+            strength: 3,
+            dimensions: 4,
+        };
+        let oa = bush.gen().unwrap();
+        assert!(rao_bound(3, 3, 4) == 21);
+        assert!(rao_bound(3, 3, 4) <= oa.points.nrows() as u64);
+
+        assert!(rao_bound(4, 2, 2) == 7);
+        assert!(rao_bound(2, 3, 4) == 8);
+        assert!(rao_bound(u64::MAX, 4, 100) == u64::MAX);
+    }
+
+    #[test]
+    fn test_subsample_runs() {
+        let bose = Bose {
+            prime_base: 5,
+            dimensions: 4,
+        };
+        let oa = bose.gen().unwrap();
+        let index = oa.build_index();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let subsample = oa.subsample_runs(10, &mut rng).unwrap();
+        assert!(subsample.dim() == (10, 4));
+
+        // Every row should come from the original array, and no row should be drawn twice
+        let mut rows: Vec<usize> = subsample
+            .rows()
+            .into_iter()
+            .map(|row| index.lookup(&row.to_vec()).unwrap())
+            .collect();
+        rows.sort_unstable();
+        rows.dedup();
+        assert!(rows.len() == 10);
+
+        assert!(oa.subsample_runs(25, &mut rng).unwrap().nrows() == 25);
+        assert!(oa.subsample_runs(26, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_decode_with() {
+        let bose = Bose {
+            prime_base: 2,
+            dimensions: 2,
+        };
+        let oa = bose.gen().unwrap();
+        let labels = vec![vec!["low".to_owned(), "high".to_owned()]];
+        let decoded = oa.decode_with(&labels).unwrap();
+        assert!(
+            decoded
+                == vec![
+                    vec!["low", "low"],
+                    vec!["low", "high"],
+                    vec!["high", "low"],
+                    vec!["high", "high"],
+                ]
+        );
+
+        let labels = vec![
+            vec!["low".to_owned(), "high".to_owned()],
+            vec!["off".to_owned(), "on".to_owned()],
+        ];
+        let decoded = oa.decode_with(&labels).unwrap();
+        assert!(decoded[1] == vec!["low", "on"]);
+
+        let labels = vec![vec!["low".to_owned()]];
+        assert!(oa.decode_with(&labels).is_err());
+        assert!(oa.decode_with(&[]).is_err());
+    }
+
+    #[test]
+    fn test_verify_with_index() {
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        let oa = bose.gen().unwrap();
+        let mut replicated = oa.vstack(&oa).unwrap();
+
+        // The stored index is ignored in favor of the expected index
+        replicated.index = 1;
+        assert!(verify_with_index(&replicated, 2));
+        assert!(!verify_with_index(&replicated, 1));
+        assert!(verify_with_index(&oa, 1));
+    }
+
+    #[test]
+    fn test_verify_saturated_tuple_count() {
+        // levels^strength saturates at u64::MAX, which must be rejected without counting every
+        // possible tuple
+        let oa: OA<u64> = OA {
+            levels: 1 << 40,
+            strength: 2,
+            factors: 2,
+            index: 1,
+            points: arr2(&[[0, 1], [1, 0]]),
+        };
+        assert!(oa.expected_tuple_count() == u64::MAX);
+        assert!(!verify(&oa).unwrap());
+        assert!(!verify_with_index(&oa, 1));
+    }
+
+    #[test]
+    fn test_best_projection() {
+        let bush = Bush {
+            prime_base: 5,
+            strength: 2,
+            dimensions: 6,
+        };
+        let oa = bush.gen().unwrap();
+        let (columns, score) = best_projection(&oa, 3).unwrap();
+        assert!(columns.len() == 3);
+        assert!(columns.windows(2).all(|w| w[0] < w[1]));
+        assert!(columns.iter().all(|&j| j < 6));
+        assert!(score >= 0.0);
+
+        let points: Array2<f64> =
+            normalize_with_placement(&oa, 0.0, false, Placement::Centered, &mut thread_rng())
+                .unwrap();
+
+        for selection in (0..6).combinations(3) {
+            assert!(score <= centered_l2_discrepancy(&points.select(Axis(1), &selection)));
+        }
+        assert!(best_projection(&oa, 0).is_err());
+        assert!(best_projection(&oa, 7).is_err());
+    }
+
+    #[test]
+    fn test_foldover() {
+        let bose = Bose {
+            prime_base: 2,
+            dimensions: 3,
+        };
+        let oa = bose.gen().unwrap();
+        let folded = oa.foldover().unwrap();
+        assert!(folded.points.nrows() == 8);
+        assert!(folded.index == 2);
+        assert!(folded.points.slice(s![4.., ..]) == oa.points.mapv(|x| 1 - x));
+        assert!(verify(&folded).unwrap());
+
+        // The foldover of this strength 2 array is the full factorial, which has a strength of 3
+        let raised = OA {
+            strength: 3,
+            index: 1,
+            ..folded
+        };
+        assert!(verify(&raised).unwrap());
+
+        let mut corrupted = bose.gen().unwrap();
+        corrupted.points[[0, 0]] = 2;
+        assert!(corrupted.foldover().is_err());
+    }
+
+    #[test]
+    fn test_iter_projection() {
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        let oa = bose.gen().unwrap();
+        let mut counts: HashMap<Vec<u32>, usize> = HashMap::new();
+
+        for tuple in oa.iter_projection(&[0, 1]) {
+            *counts.entry(tuple).or_insert(0) += 1;
+        }
+        assert!(counts.len() == 9);
+        assert!(counts.values().all(|&count| count == 1));
+
+        let projection: Vec<Vec<u32>> = oa.iter_projection(&[2, 0]).take(4).collect();
+        assert!(projection == vec![vec![0, 0], vec![1, 0], vec![2, 0], vec![1, 1]]);
+    }
+
+    #[test]
+    fn test_defining_relation() {
+        // The third column of a base 2 Bose array is the XOR of the first two
+        let bose = Bose {
+            prime_base: 2,
+            dimensions: 3,
+        };
+        let oa = bose.gen().unwrap();
+        assert!(defining_relation(&oa) == vec![vec![0, 1, 2]]);
+
+        // A 2^(5-2) design with the generators D = AB and E = AC
+        let points = Array2::from_shape_fn((8, 5), |(i, j)| {
+            let (a, b, c) = ((i >> 2) & 1, (i >> 1) & 1, i & 1);
+            [a, b, c, a ^ b, a ^ c][j] as u32
+        });
+        let oa = OA {
+            strength: 2,
+            levels: 2,
+            index: 2,
+            factors: 5,
+            points,
+        };
+        assert!(defining_relation(&oa) == vec![vec![0, 1, 3], vec![0, 2, 4], vec![1, 2, 3, 4]]);
+
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        assert!(defining_relation(&bose.gen().unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_kronecker() {
+        let a = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        }
+        .gen()
+        .unwrap();
+        let b = Bose {
+            prime_base: 3,
+            dimensions: 2,
+        }
+        .gen()
+        .unwrap();
+        let product = kronecker(&a, &b).unwrap();
+        assert!(product.points.dim() == (81, 6));
+        assert!(product.factors == 6);
+        assert!(product.strength == 2);
+        assert!(product.index == 9);
+        assert!(verify(&product).unwrap());
+
+        let c = Bush {
+            prime_base: 3,
+            strength: 3,
+            dimensions: 4,
+        }
+        .gen()
+        .unwrap();
+        let product = kronecker(&c, &a).unwrap();
+        assert!(product.points.dim() == (243, 12));
+        assert!(verify(&product).unwrap());
+
+        // The number of runs of the result overflows, even though neither array has any entries
+        let tall = OA {
+            levels: 3,
+            strength: 1,
+            factors: 0,
+            index: 1,
+            points: Array2::<u32>::zeros((1 << 40, 0)),
+        };
+        assert!(kronecker(&tall, &tall).is_err());
+
+        let d = Bose {
+            prime_base: 5,
+            dimensions: 2,
+        }
+        .gen()
+        .unwrap();
+        assert!(kronecker(&a, &d).is_err());
+    }
+
+    #[test]
+    fn test_from_rows() {
+        let rows = vec![
+            vec![0, 0, 0],
+            vec![0, 1, 1],
+            vec![0, 2, 2],
+            vec![1, 0, 1],
+            vec![1, 1, 2],
+            vec![1, 2, 0],
+            vec![2, 0, 2],
+            vec![2, 1, 0],
+            vec![2, 2, 1],
+        ];
+        let oa = OA::from_rows(rows.clone(), 2).unwrap();
+        assert!(oa.levels == 3);
+        assert!(oa.factors == 3);
+        assert!(oa.index == 1);
+        assert!(oa.points.row(5) == aview1(&[1, 2, 0]));
+
+        let mut ragged = rows.clone();
+        ragged[4].push(0);
+        assert!(OA::from_rows(ragged, 2).is_err());
+
+        let mut unbalanced = rows;
+        unbalanced[4] = vec![1, 0, 1];
+        assert!(OA::from_rows(unbalanced, 2).is_err());
+        assert!(OA::<u32>::from_rows(Vec::new(), 2).is_err());
+
+        // 16^2 overflows a u8, so `levels^strength` is out of range rather than wrapping
+        let overflow: Vec<Vec<u8>> = vec![vec![15, 0], vec![0, 15]];
+        assert!(OA::from_rows(overflow, 2).is_err());
+    }
+
+    #[test]
+    fn test_column_imbalance() {
+        let bose = Bose {
+            prime_base: 5,
+            dimensions: 4,
+        };
+        let mut oa = bose.gen().unwrap();
+        assert!(column_imbalance(&oa) == vec![0.0; 4]);
+
+        // Moving one entry from level 0 to level 1 changes the counts to [4, 6, 5, 5, 5]
+        let row = oa.points.column(2).iter().position(|&x| x == 0).unwrap();
+        oa.points[[row, 2]] = 1;
+        let imbalance = column_imbalance(&oa);
+        assert!(imbalance[0] == 0.0 && imbalance[1] == 0.0 && imbalance[3] == 0.0);
+        assert!((imbalance[2] - 0.4).abs() < 1e-12);
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn test_verify_sampled_par() {
+        let bose = Bose {
+            prime_base: 11,
+            dimensions: 12,
+        };
+        let mut oa = bose.gen().unwrap();
+        assert!(verify_sampled_par(&oa, 10, &RngConfig::seeded(0)));
+
+        // With a single corrupted column, a small sample only sometimes finds an unbalanced
+        // combination, but the result for each seed should be the same every time
+        oa.points[[0, 3]] = 1;
+        let results: Vec<bool> = (0..20)
+            .map(|seed| verify_sampled_par(&oa, 3, &RngConfig::seeded(seed)))
+            .collect();
+
+        for (seed, &result) in (0..20).zip(results.iter()) {
+            assert!(verify_sampled_par(&oa, 3, &RngConfig::seeded(seed)) == result);
+        }
+        assert!(results.contains(&true) && results.contains(&false));
+        assert!(!verify_sampled_par(&oa, 100, &RngConfig::seeded(0)));
+    }
+
+    #[test]
+    fn test_ensure_valid() {
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        assert!(ensure_valid(bose.gen().unwrap()).is_ok());
+
+        // Simulate a broken constructor by corrupting its output
+        let mut oa = bose.gen().unwrap();
+        oa.points[[0, 0]] = 1;
+        assert!(ensure_valid(oa).is_err());
+    }
+
+    #[test]
+    fn test_normalize_full_jitter_stays_in_strata() {
+        let bose = Bose {
+            prime_base: 7,
+            dimensions: 4,
+        };
+        let oa = bose.gen().unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..200 {
+            let points: Array2<f32> = normalize_with_rng(&oa, 1.0, false, &mut rng).unwrap();
+
+            for (&x, &p) in oa.points.iter().zip(points.iter()) {
+                assert!(p >= x as f32 / 7.0);
+                assert!(p < (x + 1) as f32 / 7.0);
+            }
+        }
+
+        // A point on the upper boundary of its strata is moved just below it
+        assert!(clamp_to_strata(3.0f64, 2.0, 3.0) < 1.0);
+        assert!(clamp_to_strata(3.0f32, 2.0, 3.0) < 1.0);
+        assert!(clamp_to_strata(2.0f64, 1.0, 3.0) < 2.0 / 3.0);
+        assert!(clamp_to_strata(1.5f64, 1.0, 3.0) == 0.5);
+    }
+
+    #[test]
+    fn test_observed_levels() {
+        let bose = Bose {
+            prime_base: 5,
+            dimensions: 4,
+        };
+        let mut oa = bose.gen().unwrap();
+        assert!(oa.observed_levels() == 5);
+
+        oa.levels = 7;
+        assert!(oa.observed_levels() == 5);
+
+        let empty = OA {
+            strength: 2,
+            levels: 2,
+            factors: 0,
+            index: 1,
+            points: Array2::<u32>::zeros((0, 0)),
+        };
+        assert!(empty.observed_levels() == 0);
+    }
+
+    #[test]
+    fn test_to_indicator_matrix() {
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 2,
+        };
+        let oa = bose.gen().unwrap();
+        let design = to_indicator_matrix(&oa);
+        assert!(design.dim() == (9, 4));
+
+        // Every factor has a single indicator set, unless it's at the reference level
+        for (row, run) in design.rows().into_iter().zip(oa.points.rows()) {
+            for (factor, &level) in run.iter().enumerate() {
+                let indicators = row.slice(ndarray::s![factor * 2..factor * 2 + 2]);
+                assert!(indicators.sum() == if level == 0 { 0.0 } else { 1.0 });
+
+                if level > 0 {
+                    assert!(indicators[level as usize - 1] == 1.0);
+                }
+            }
+        }
+
+        // Every level appears three times in a column of the array
+        assert!(design.sum_axis(Axis(0)).iter().all(|&x| x == 3.0));
+    }
+
+    #[test]
+    fn test_is_regular_fraction() {
+        let bose = Bose {
+            prime_base: 2,
+            dimensions: 3,
+        };
+        let oa = bose.gen().unwrap();
+        assert!(is_regular_fraction(&oa));
+        assert!(is_regular_fraction(&oa.replicate(2).unwrap()));
+
+        // The 12 run Plackett-Burman design is balanced, but it isn't a regular fraction
+        let generator = [1, 1, 0, 1, 1, 1, 0, 0, 0, 1, 0];
+        let plackett_burman = OA {
+            strength: 2,
+            levels: 2,
+            factors: 11,
+            index: 3,
+            points: Array2::from_shape_fn((12, 11), |(i, j)| {
+                if i == 11 {
+                    0
+                } else {
+                    generator[(i + j) % 11]
+                }
+            }),
+        };
+        assert!(verify(&plackett_burman).unwrap());
+        assert!(!is_regular_fraction(&plackett_burman));
+
+        // A 3-level array is never a regular 2-level fraction
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        assert!(!is_regular_fraction(&bose.gen().unwrap()));
+    }
+
+    #[test]
+    fn test_resolvable_blocks() {
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        let oa = bose.gen().unwrap();
+        let blocks = oa.resolvable_blocks(3).unwrap();
+        assert!(blocks.len() == 3);
+
+        let mut all_runs: Vec<usize> = blocks.iter().flatten().copied().collect();
+        all_runs.sort_unstable();
+        assert!(all_runs == (0..9).collect::<Vec<_>>());
+
+        // Every level appears once in every column of every block
+        for block in &blocks {
+            assert!(block.len() == 3);
+
+            for col in oa.points.columns() {
+                let levels: HashSet<u32> = block.iter().map(|&i| col[i]).collect();
+                assert!(levels.len() == 3);
+            }
+        }
+        assert!(oa.resolvable_blocks(1).unwrap() == vec![(0..9).collect::<Vec<_>>()]);
+        assert!(oa.resolvable_blocks(2).is_err());
+        assert!(oa.resolvable_blocks(0).is_err());
+
+        // Every direction is a column when there are `p + 1` dimensions, so there is no partition
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 4,
+        };
+        assert!(bose.gen().unwrap().resolvable_blocks(3).is_err());
+    }
+
+    #[test]
+    fn test_resolvable_blocks_limit() {
+        // The search has to visit at least one partial assignment for every run
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        let oa = bose.gen().unwrap();
+        assert!(matches!(
+            oa.resolvable_blocks_with_limit(3, 8),
+            Err(OarsError::RuntimeError(_))
+        ));
+        assert!(oa.resolvable_blocks_with_limit(3, 1000).is_ok());
+    }
+
+    #[test]
+    fn test_is_prime_power_levels() {
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        let mut oa = bose.gen().unwrap();
+        assert!(oa.is_prime_power_levels());
+
+        for (levels, expected) in [(4, true), (8, true), (6, false), (1, false)] {
+            oa.levels = levels;
+            assert!(oa.is_prime_power_levels() == expected);
+        }
     }
 }
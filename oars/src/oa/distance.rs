@@ -0,0 +1,156 @@
+//! Distance-based measures of how well a point set fills the unit hypercube.
+
+use crate::utils::Float;
+use itertools::Itertools;
+use ndarray::{Array2, ArrayView1};
+
+/// The metric used to measure the distance between two points in the unit hypercube
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Metric {
+    /// The usual Euclidean distance
+    #[default]
+    Euclidean,
+
+    /// The Euclidean distance on the unit torus, where each axis wraps around from 1 to 0. The
+    /// difference along each axis is at most 0.5, which avoids boundary effects.
+    Toroidal,
+}
+
+impl Metric {
+    /// Compute the distance between two points with the metric
+    fn distance<U: Float>(&self, a: ArrayView1<U>, b: ArrayView1<U>) -> U {
+        let one = U::one();
+
+        a.iter()
+            .zip(b.iter())
+            .fold(U::zero(), |acc, (&x, &y)| {
+                let diff = (x - y).abs();
+                let diff = match self {
+                    Metric::Euclidean => diff,
+                    Metric::Toroidal => diff.min(one - diff),
+                };
+                acc + diff * diff
+            })
+            .sqrt()
+    }
+}
+
+/// Approximate the fill distance (or coverage radius) of a point set in $[0, 1)^d$, which is the
+/// largest distance from any location in the unit hypercube to its nearest point.
+///
+/// The unit hypercube is divided into `grid_resolution` cells along each axis, and the distance
+/// is only measured from the center of each cell, so a finer grid yields a better approximation.
+/// This takes $O(r^d n d)$ time for a resolution of $r$, so the resolution should be kept small
+/// for high dimensional point sets. A lower fill distance means that there are no large gaps in
+/// the point set. This returns NaN if the point set or the grid is empty.
+pub fn fill_distance<U: Float>(points: &Array2<U>, grid_resolution: usize, metric: Metric) -> U {
+    let (n, d) = points.dim();
+
+    if n == 0 || grid_resolution == 0 {
+        return U::nan();
+    }
+    let res = U::from(grid_resolution).unwrap();
+    let half = U::from(0.5).unwrap();
+
+    (0..d)
+        .map(|_| 0..grid_resolution)
+        .multi_cartesian_product()
+        .map(|cell| {
+            let center: Vec<U> = cell
+                .iter()
+                .map(|&i| (U::from(i).unwrap() + half) / res)
+                .collect();
+            let center = ArrayView1::from(&center[..]);
+
+            points
+                .rows()
+                .into_iter()
+                .map(|point| metric.distance(point, center))
+                .fold(U::infinity(), U::min)
+        })
+        .fold(U::zero(), U::max)
+}
+
+/// Compute the smallest distance between any two points in a point set, as measured by `metric`.
+///
+/// Each row of `points` is a point. This compares every pair of points, which takes $O(n^2 d)$
+/// time. This returns NaN if there are fewer than two points.
+pub fn min_distance<U: Float>(points: &Array2<U>, metric: Metric) -> U {
+    if points.nrows() < 2 {
+        return U::nan();
+    }
+    points
+        .rows()
+        .into_iter()
+        .tuple_combinations()
+        .map(|(a, b)| metric.distance(a, b))
+        .fold(U::infinity(), U::min)
+}
+
+/// Score a point set by the maximin criterion, which is its minimum Euclidean distance between any
+/// two points.
+///
+/// A maximin design maximizes this score, so a higher score means that the points are spread
+/// further apart. This is the same as `min_distance` with `Metric::Euclidean`, and takes
+/// $O(n^2 d)$ time.
+pub fn maximin_score<U: Float>(points: &Array2<U>) -> U {
+    min_distance(points, Metric::Euclidean)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::arr2;
+
+    #[test]
+    fn test_fill_distance() {
+        let sparse = arr2(&[[0.25f64, 0.25], [0.75, 0.75]]);
+        let dense = arr2(&[[0.25f64, 0.25], [0.75, 0.75], [0.25, 0.75], [0.75, 0.25]]);
+        let sparse_fill = fill_distance(&sparse, 20, Metric::Euclidean);
+        let dense_fill = fill_distance(&dense, 20, Metric::Euclidean);
+        assert!(dense_fill < sparse_fill);
+
+        // The farthest cell centers from a single point at the center are the corner cells
+        let center = arr2(&[[0.5f64, 0.5]]);
+        let expected = (2.0 * 0.475f64.powi(2)).sqrt();
+        assert!((fill_distance(&center, 20, Metric::Euclidean) - expected).abs() < 1e-12);
+
+        assert!(fill_distance(&Array2::<f64>::zeros((0, 2)), 20, Metric::Euclidean).is_nan());
+        assert!(fill_distance(&dense, 0, Metric::Euclidean).is_nan());
+    }
+
+    #[test]
+    fn test_min_distance() {
+        // A 4 x 4 grid with a spacing of 0.25
+        let grid = Array2::from_shape_fn((16, 2), |(i, j)| {
+            if j == 0 {
+                (i / 4) as f64 / 4.0
+            } else {
+                (i % 4) as f64 / 4.0
+            }
+        });
+        assert!(min_distance(&grid, Metric::Euclidean) == 0.25);
+        assert!(maximin_score(&grid) == 0.25);
+
+        let points = arr2(&[[0.0f64, 0.0], [0.3, 0.4], [1.0, 1.0]]);
+        assert!((min_distance(&points, Metric::Euclidean) - 0.5).abs() < 1e-12);
+
+        assert!(min_distance(&arr2(&[[0.5f64, 0.5]]), Metric::Toroidal).is_nan());
+    }
+
+    #[test]
+    fn test_toroidal_metric() {
+        // The points are near opposite edges of the unit square, so they are close on the torus
+        let points = arr2(&[[0.05f64, 0.5], [0.95, 0.5]]);
+        assert!((min_distance(&points, Metric::Euclidean) - 0.9).abs() < 1e-12);
+        assert!((min_distance(&points, Metric::Toroidal) - 0.1).abs() < 1e-12);
+
+        // A point near a corner is far from the opposite corner, unless the square wraps around
+        let corner = arr2(&[[0.05f64, 0.05]]);
+        let euclidean = fill_distance(&corner, 20, Metric::Euclidean);
+        let toroidal = fill_distance(&corner, 20, Metric::Toroidal);
+        assert!((euclidean - (2.0 * 0.925f64.powi(2)).sqrt()).abs() < 1e-12);
+        assert!(toroidal <= 0.5f64.hypot(0.5));
+        assert!(Metric::default() == Metric::Euclidean);
+    }
+}
@@ -0,0 +1,108 @@
+//! Orthogonal arrays with the number of factors encoded in their type.
+
+use super::OA;
+use crate::utils::{Integer, OarsError, OarsResult};
+
+/// An orthogonal array with `F` factors, where the number of factors is part of the type.
+///
+/// This is a thin wrapper around `OA`, which lets the compiler catch combinations of arrays with
+/// a different number of factors. The number of factors is checked once, when the array is
+/// wrapped.
+///
+/// ```
+/// use oars::constructors::Bose;
+/// use oars::oa::{OAConstructor, OaN};
+///
+/// let a: OaN<3, u32> = OaN::new(Bose { prime_base: 3, dimensions: 3 }.gen().unwrap()).unwrap();
+/// let b: OaN<3, u32> = OaN::new(Bose { prime_base: 3, dimensions: 3 }.gen().unwrap()).unwrap();
+/// let stacked = a.vstack(&b).unwrap();
+/// assert!(stacked.as_oa().points.dim() == (18, 3));
+/// ```
+///
+/// Stacking arrays with a different number of factors doesn't compile:
+///
+/// ```compile_fail
+/// use oars::constructors::Bose;
+/// use oars::oa::{OAConstructor, OaN};
+///
+/// let a: OaN<3, u32> = OaN::new(Bose { prime_base: 3, dimensions: 3 }.gen().unwrap()).unwrap();
+/// let b: OaN<4, u32> = OaN::new(Bose { prime_base: 3, dimensions: 4 }.gen().unwrap()).unwrap();
+/// let stacked = a.vstack(&b);
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct OaN<const F: usize, T: Integer> {
+    /// The wrapped orthogonal array, which always has `F` factors
+    oa: OA<T>,
+}
+
+impl<const F: usize, T: Integer> OaN<F, T> {
+    /// Wrap an orthogonal array, checking that it has `F` factors.
+    ///
+    /// This returns an error if either the `factors` field or the number of columns in `points`
+    /// is not `F`.
+    pub fn new(oa: OA<T>) -> OarsResult<Self> {
+        if oa.points.ncols() != F || oa.factors.to_usize() != Some(F) {
+            return Err(OarsError::InvalidParams(format!(
+                "The orthogonal array must have exactly {} factors",
+                F
+            )));
+        }
+        Ok(Self { oa })
+    }
+
+    /// Get a reference to the wrapped orthogonal array
+    pub fn as_oa(&self) -> &OA<T> {
+        &self.oa
+    }
+
+    /// Unwrap the orthogonal array
+    pub fn into_inner(self) -> OA<T> {
+        self.oa
+    }
+
+    /// Stack the runs of another orthogonal array with the same number of factors below the runs
+    /// of this one.
+    ///
+    /// This is the same as `OA::vstack`, except that the number of factors is checked at compile
+    /// time.
+    pub fn vstack(&self, other: &OaN<F, T>) -> OarsResult<OaN<F, T>> {
+        Ok(OaN {
+            oa: self.oa.vstack(&other.oa)?,
+        })
+    }
+}
+
+impl<const F: usize, T: Integer> TryFrom<OA<T>> for OaN<F, T> {
+    type Error = OarsError;
+
+    fn try_from(oa: OA<T>) -> OarsResult<Self> {
+        Self::new(oa)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constructors::Bose;
+    use crate::oa::OAConstructor;
+
+    #[test]
+    fn test_oan() {
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 3,
+        };
+        let a: OaN<3, u32> = OaN::new(bose.gen().unwrap()).unwrap();
+        let b: OaN<3, u32> = bose.gen().unwrap().try_into().unwrap();
+        let stacked = a.vstack(&b).unwrap();
+        assert!(stacked.as_oa().points.dim() == (18, 3));
+        assert!(stacked.into_inner().index == 2);
+
+        assert!(OaN::<4, u32>::new(bose.gen().unwrap()).is_err());
+
+        // The metadata has to match the points as well
+        let mut oa = bose.gen().unwrap();
+        oa.factors = 4;
+        assert!(OaN::<3, u32>::new(oa).is_err());
+    }
+}
@@ -0,0 +1,317 @@
+//! Utilities to read orthogonal arrays from, and write point sets to, plain text formats.
+
+use super::{check_levels, compute_index, from_points_verified, OA};
+use crate::utils::{as_usize, Float, Integer, OarsError, OarsResult};
+use ndarray::Array2;
+use std::io::{BufRead, BufReader, Read, Write};
+
+/// Parse a single field from a text file as an integer
+fn parse_entry<T: Integer>(field: &str) -> OarsResult<T> {
+    let field = field.trim();
+    T::from_str_radix(field, 10)
+        .map_err(|_| OarsError::InvalidParams(format!("Could not parse `{}` as an integer", field)))
+}
+
+/// Read an orthogonal array from CSV data and verify that it is valid.
+///
+/// Each non-empty line of the input is a run of the array, with the factors separated by commas.
+/// The number of levels is inferred as the largest entry plus one, and the index is inferred from
+/// the number of runs. This returns an error if the rows don't all have the same number of
+/// factors, or if the data is not a valid orthogonal array with the given strength.
+pub fn read_csv_verified<T: Integer, R: Read>(reader: R, strength: T) -> OarsResult<OA<T>> {
+    let mut entries = Vec::new();
+    let mut factors = None;
+    let mut runs = 0;
+
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+        let row = line
+            .split(',')
+            .map(parse_entry)
+            .collect::<OarsResult<Vec<T>>>()?;
+
+        match factors {
+            None => factors = Some(row.len()),
+            Some(n) if n != row.len() => {
+                return Err(OarsError::InvalidParams(
+                    "Every row must have the same number of factors".to_owned(),
+                ))
+            }
+            _ => (),
+        }
+        entries.extend(row);
+        runs += 1;
+    }
+    let points = Array2::from_shape_vec((runs, factors.unwrap_or(0)), entries)?;
+    from_points_verified(points, strength)
+}
+
+/// Read an orthogonal array from a whitespace-delimited text table, as published by Art Owen and
+/// others.
+///
+/// The first non-empty line is a header with the number of runs, the number of factors, the
+/// number of levels, and the strength of the array, in that order (`n m s t`). Every line after
+/// the header is a run of the array. The index of the array is computed from the header. This
+/// returns an error if the header is malformed, or if the dimensions or the entries of the table
+/// don't match the header.
+pub fn read_owen_table<R: Read, T: Integer>(reader: R) -> OarsResult<OA<T>> {
+    let mut lines = BufReader::new(reader).lines();
+    let mut header = None;
+
+    for line in lines.by_ref() {
+        let line = line?;
+
+        if !line.trim().is_empty() {
+            header = Some(line);
+            break;
+        }
+    }
+    let header = header
+        .ok_or_else(|| OarsError::InvalidParams("The table does not have a header".to_owned()))?
+        .split_whitespace()
+        .map(parse_entry)
+        .collect::<OarsResult<Vec<T>>>()?;
+
+    if header.len() != 4 {
+        return Err(OarsError::InvalidParams(
+            "The header must have exactly four values: `n m s t`".to_owned(),
+        ));
+    }
+    let (runs, factors, levels, strength) = (header[0], header[1], header[2], header[3]);
+    let runs_usize = as_usize(runs)?;
+    let factors_usize = as_usize(factors)?;
+    let index = compute_index(runs_usize, levels, strength)?;
+
+    // The header can't be trusted to size the buffer, since a malformed or malicious header could
+    // request an enormous allocation
+    let mut entries = Vec::new();
+    let mut rows = 0;
+
+    for line in lines {
+        let line = line?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+        let row = line
+            .split_whitespace()
+            .map(parse_entry)
+            .collect::<OarsResult<Vec<T>>>()?;
+
+        if row.len() != factors_usize {
+            return Err(OarsError::InvalidParams(format!(
+                "Row {} has {} factors, but the header specifies {}",
+                rows,
+                row.len(),
+                factors_usize
+            )));
+        }
+        entries.extend(row);
+        rows += 1;
+    }
+
+    if rows != runs_usize {
+        return Err(OarsError::InvalidParams(format!(
+            "The table has {} runs, but the header specifies {}",
+            rows, runs_usize
+        )));
+    }
+
+    let points = Array2::from_shape_vec((runs_usize, factors_usize), entries)?;
+    check_levels(&points, levels)?;
+
+    Ok(OA {
+        levels,
+        strength,
+        factors,
+        index,
+        points,
+    })
+}
+
+/// Write a point set in the space-delimited format that gnuplot reads.
+///
+/// Every point is written on its own line, with its coordinates separated by spaces. The point
+/// set is written as a single data block, so it can be plotted directly as a scatter plot. Use
+/// `write_gnuplot_blocks` to split the points into separate data sets.
+pub fn write_gnuplot<U: Float, W: Write>(points: &Array2<U>, w: W) -> OarsResult<()> {
+    write_gnuplot_blocks(points, points.nrows().max(1), w)
+}
+
+/// Write a point set in the space-delimited format that gnuplot reads, splitting the points into
+/// data sets of `block_size` points each.
+///
+/// This is the same as `write_gnuplot`, except that consecutive data sets are separated by two
+/// blank lines, so each one can be selected with gnuplot's `index` keyword, such as the runs that
+/// were generated by each replicate of an array. The last data set has fewer points if the
+/// number of points isn't a multiple of `block_size`.
+///
+/// This returns an error if `block_size` is 0.
+pub fn write_gnuplot_blocks<U: Float, W: Write>(
+    points: &Array2<U>,
+    block_size: usize,
+    mut w: W,
+) -> OarsResult<()> {
+    if block_size == 0 {
+        return Err(OarsError::InvalidParams(
+            "`block_size` must be greater than 0".to_owned(),
+        ));
+    }
+
+    for (i, row) in points.rows().into_iter().enumerate() {
+        if i > 0 && i % block_size == 0 {
+            writeln!(w)?;
+            writeln!(w)?;
+        }
+        let line: Vec<String> = row
+            .iter()
+            .map(|x| x.to_f64().unwrap_or(f64::NAN).to_string())
+            .collect();
+        writeln!(w, "{}", line.join(" "))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constructors::Bose;
+    use crate::oa::{normalize, verify, OAConstructor};
+
+    #[test]
+    fn test_read_csv_verified() {
+        let bose = Bose {
+            prime_base: 5,
+            dimensions: 4,
+        };
+        let oa = bose.gen().unwrap();
+        let mut buffer = Vec::new();
+
+        for row in oa.points.rows() {
+            let line: Vec<String> = row.iter().map(|x| x.to_string()).collect();
+            writeln!(buffer, "{}", line.join(",")).unwrap();
+        }
+        let loaded: OA<u32> = read_csv_verified(buffer.as_slice(), 2).unwrap();
+        assert!(loaded.points == oa.points);
+        assert!(loaded.levels == 5);
+        assert!(loaded.factors == 4);
+        assert!(loaded.index == 1);
+    }
+
+    #[test]
+    fn test_read_csv_invalid() {
+        // Duplicate a row so the array is no longer balanced
+        let data = "0,0\n0,1\n1,0\n0,1\n";
+        assert!(read_csv_verified::<u32, _>(data.as_bytes(), 2).is_err());
+
+        let data = "0,0\n0,1\n1,0,1\n1,1\n";
+        assert!(read_csv_verified::<u32, _>(data.as_bytes(), 2).is_err());
+
+        let data = "0,0\n0,a\n1,0\n1,1\n";
+        assert!(read_csv_verified::<u32, _>(data.as_bytes(), 2).is_err());
+    }
+
+    /// A strength 2 orthogonal array with 9 runs, 4 factors, and 3 levels
+    const OWEN_TABLE: &str = "9 4 3 2
+        0 0 0 0
+        0 1 1 2
+        0 2 2 1
+        1 0 1 1
+        1 1 2 0
+        1 2 0 2
+        2 0 2 2
+        2 1 0 1
+        2 2 1 0
+    ";
+
+    #[test]
+    fn test_read_owen_table() {
+        let oa: OA<u32> = read_owen_table(OWEN_TABLE.as_bytes()).unwrap();
+        assert!(oa.points.dim() == (9, 4));
+        assert!(oa.levels == 3);
+        assert!(oa.strength == 2);
+        assert!(oa.factors == 4);
+        assert!(oa.index == 1);
+        assert!(verify(&oa).unwrap());
+    }
+
+    #[test]
+    fn test_read_owen_table_mismatched_header() {
+        let table = OWEN_TABLE.replacen("9 4 3 2", "9 5 3 2", 1);
+        assert!(read_owen_table::<_, u32>(table.as_bytes()).is_err());
+
+        let table = OWEN_TABLE.replacen("9 4 3 2", "18 4 3 2", 1);
+        assert!(read_owen_table::<_, u32>(table.as_bytes()).is_err());
+
+        let table = OWEN_TABLE.replacen("9 4 3 2", "9 4 2 2", 1);
+        assert!(read_owen_table::<_, u32>(table.as_bytes()).is_err());
+
+        let table = OWEN_TABLE.replacen("9 4 3 2", "9 4 3", 1);
+        assert!(read_owen_table::<_, u32>(table.as_bytes()).is_err());
+
+        // 1000^4 overflows a u32
+        let table = OWEN_TABLE.replacen("9 4 3 2", "81 4 1000 4", 1);
+        assert!(read_owen_table::<_, u32>(table.as_bytes()).is_err());
+
+        // The header asks for far more entries than could be allocated
+        let table = OWEN_TABLE.replacen("9 4 3 2", "900000000000 4000000000 3 2", 1);
+        assert!(read_owen_table::<_, u64>(table.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_write_gnuplot() {
+        let bose = Bose {
+            prime_base: 5,
+            dimensions: 3,
+        };
+        let oa = bose.gen().unwrap();
+        let points: Array2<f64> = normalize(&oa, 1.0, true).unwrap();
+        let mut buffer = Vec::new();
+        write_gnuplot(&points, &mut buffer).unwrap();
+
+        let output = String::from_utf8(buffer).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines.len() == 25);
+
+        for (line, row) in lines.iter().zip(points.rows()) {
+            let coords: Vec<f64> = line
+                .split_whitespace()
+                .map(|x| x.parse().unwrap())
+                .collect();
+            assert!(coords.len() == 3);
+            assert!(coords.iter().zip(row.iter()).all(|(a, b)| a == b));
+        }
+    }
+
+    #[test]
+    fn test_write_gnuplot_blocks() {
+        let bose = Bose {
+            prime_base: 3,
+            dimensions: 2,
+        };
+        let points: Array2<f64> = normalize(&bose.gen().unwrap(), 0.0, false).unwrap();
+        let mut buffer = Vec::new();
+        write_gnuplot_blocks(&points, 4, &mut buffer).unwrap();
+
+        // 9 points in blocks of 4 give blocks of 4, 4, and 1 points
+        let output = String::from_utf8(buffer).unwrap();
+        let blocks: Vec<usize> = output
+            .split("\n\n\n")
+            .map(|block| block.lines().count())
+            .collect();
+        assert!(blocks == vec![4, 4, 1]);
+        assert!(write_gnuplot_blocks(&points, 0, Vec::new()).is_err());
+
+        // Without blocks, the output shouldn't have any blank lines
+        let mut buffer = Vec::new();
+        write_gnuplot(&points, &mut buffer).unwrap();
+        assert!(String::from_utf8(buffer)
+            .unwrap()
+            .lines()
+            .all(|line| !line.is_empty()));
+    }
+}
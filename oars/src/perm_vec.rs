@@ -2,6 +2,7 @@
 //! randomly shuffle orthogonal arrays, or shuffle any set.
 
 use rand::seq::SliceRandom;
+use rand::Rng;
 use std::ops::Index;
 
 /// This is a vector containing the elements ${0, 1 \cdots n - 1}$, shuffled
@@ -33,9 +34,8 @@ impl PermutationVector {
         }
     }
 
-    /// Randomly shuffle the permutation vector
-    pub fn shuffle(&mut self) {
-        let mut rng = rand::thread_rng();
-        self.vec.shuffle(&mut rng);
+    /// Randomly shuffle the permutation vector using the supplied random number generator
+    pub fn shuffle(&mut self, rng: &mut impl Rng) {
+        self.vec.shuffle(rng);
     }
 }
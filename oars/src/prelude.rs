@@ -6,4 +6,4 @@
 pub use crate::oa::ParOAConstructor;
 pub use crate::oa::{OAConstructor, OAResult, OA};
 pub use crate::soa::{SOAConstructor, SOAResult, SOA};
-pub use crate::utils::{Float, Integer, OarsError, OarsResult};
+pub use crate::utils::{Float, Integer, OarsError, OarsResult, RngConfig};
@@ -4,10 +4,13 @@
 //! for constructing SOAs.  This module also defines a few construction methods, as well as provide
 //! a verification method to ensure that the resulting points are stratified as an SOA should be.
 
-use crate::utils::OarsError;
-use itertools::{zip, Itertools};
+use crate::oa::{clamp_to_strata, column_permutations, OA};
+use crate::utils::{Integer, OarsError, OarsResult};
+use itertools::Itertools;
 use ndarray::Array2;
+use rand::Rng;
 use std::collections::{HashMap, HashSet};
+use std::iter::zip;
 
 /// A result type for strong orthogonal array construction
 pub type SOAResult = Result<SOA, OarsError>;
@@ -33,7 +36,90 @@ pub struct SOA {
     pub points: Array2<u32>,
 }
 
+impl SOA {
+    /// Get the number of levels in each column of the strong orthogonal array, which is
+    /// `base^strength`.
+    pub fn num_levels(&self) -> u32 {
+        self.base.pow(self.strength)
+    }
+
+    /// Get the parameters of the orthogonal array that underlies the strong orthogonal array, as
+    /// `(levels, strength)`. The number of levels is `base^strength`.
+    pub fn as_oa_params(&self) -> (u32, u32) {
+        (self.num_levels(), self.strength)
+    }
+}
+
+/// Construct a strong orthogonal array by multiplying the points of an orthogonal array by an
+/// explicit construction matrix.
+///
+/// This generalizes constructions such as the one from Liu and Liu, where the SOA is the product
+/// of an orthogonal array with some construction matrix. Entry $(i, j)$ of the SOA is
+/// $\sum_k D_{ik} M_{kj}$, where $D$ is the matrix of points of the orthogonal array and $M$ is
+/// the construction matrix, so the construction matrix must have a row for every factor of the
+/// orthogonal array. The base of the SOA is the number of levels of the orthogonal array.
+///
+/// The resulting SOA is not verified, since it depends on the construction matrix, so it should
+/// be checked with `verify` when experimenting with a new matrix.
+pub struct SOAFromMatrix<'a, T: Integer> {
+    /// The orthogonal array to construct the SOA from
+    pub oa: &'a OA<T>,
+
+    /// The construction matrix, which has a row for every factor of the orthogonal array and a
+    /// column for every factor of the SOA
+    pub matrix: Array2<u32>,
+
+    /// The strength of the resulting SOA
+    pub strength: u32,
+}
+
+impl<T: Integer> SOAConstructor for SOAFromMatrix<'_, T> {
+    fn gen(&self) -> SOAResult {
+        let (runs, factors) = self.oa.points.dim();
+
+        if self.matrix.nrows() != factors {
+            return Err(OarsError::InvalidParams(format!(
+                "The construction matrix has {} rows, but the orthogonal array has {} factors",
+                self.matrix.nrows(),
+                factors
+            )));
+        }
+        let out_of_range =
+            || OarsError::InvalidParams("A value does not fit in a `u32`".to_owned());
+        let base = self.oa.levels.to_u32().ok_or_else(out_of_range)?;
+        let points = self
+            .oa
+            .points
+            .iter()
+            .map(|x| x.to_u32())
+            .collect::<Option<Vec<u32>>>()
+            .ok_or_else(out_of_range)?;
+        let points = Array2::from_shape_vec((runs, factors), points)?;
+        let mut product = Array2::<u32>::zeros((runs, self.matrix.ncols()));
+
+        for ((i, j), entry) in product.indexed_iter_mut() {
+            *entry = points
+                .row(i)
+                .iter()
+                .zip(self.matrix.column(j).iter())
+                .try_fold(0u32, |acc, (&d, &m)| d.checked_mul(m)?.checked_add(acc))
+                .ok_or_else(|| {
+                    OarsError::InvalidParams(
+                        "The product of the points and the construction matrix overflows"
+                            .to_owned(),
+                    )
+                })?;
+        }
+        Ok(SOA {
+            strength: self.strength,
+            base,
+            points: product,
+        })
+    }
+}
+
 /// A nested two-dimensional vector
+#[cfg(test)]
 type Vec2D<T> = Vec<Vec<T>>;
 
 /// Recursive utility method to determine the combinations of numbers that add up to some given
@@ -42,6 +128,10 @@ type Vec2D<T> = Vec<Vec<T>>;
 /// The sum is the target sum. The reduced number is the target after a number has already
 /// been tried. `arr` is the current array of numbers that add up to the sum for the stack,
 /// and `res` is a reference to an array of vectors with the results.
+///
+/// `verify` uses the lazy `integer_partitions_iter` instead, so the eager version is only kept to
+/// test the iterator against.
+#[cfg(test)]
 fn sum_perms_helper(sum: u32, reduced_num: u32, arr: &[u32], res: &mut Vec2D<u32>) {
     if reduced_num == 0 {
         res.push(arr.to_vec());
@@ -63,26 +153,83 @@ fn sum_perms_helper(sum: u32, reduced_num: u32, arr: &[u32], res: &mut Vec2D<u32
 /// Given some desired sum, find all of the combinations of numbers that add up to the desired
 /// sum. This is used to generat the strata when verifying a strong orthogonal array.
 ///
-/// This method is a convenience wrapper for the recursive solver.
+/// This method is a convenience wrapper for the recursive solver. The combinations are sorted in
+/// ascending lexicographic order, so the output doesn't depend on the order of the recursion.
+#[cfg(test)]
 fn sum_perms(sum: u32) -> Vec2D<u32> {
     let mut res = Vec::new();
     let arr = Vec::new();
     sum_perms_helper(sum, sum, &arr, &mut res);
+    res.sort();
     res
 }
 
+/// Lazily generate all of the combinations of numbers that add up to some desired sum.
+///
+/// Each combination is yielded with its numbers in ascending order, so every partition of the sum
+/// is visited exactly once. The partitions are yielded in ascending lexicographic order, so the
+/// order is stable across runs and matches the sorted output of `sum_perms`. This avoids
+/// materializing every partition up front, which allocates a lot for large sums. This uses the
+/// "ruleAsc" algorithm from Kelleher and O'Sullivan's "Generating All Partitions: A Comparison Of
+/// Two Encodings" (2009).
+fn integer_partitions_iter(sum: u32) -> impl Iterator<Item = Vec<u32>> {
+    let n = sum as usize;
+    let mut parts = vec![0; n + 1];
+    // `k` is the index of the last part in the current partition. When it reaches 0, every
+    // partition has been generated.
+    let mut k = 1;
+    // The only partition of zero is the empty partition, which the algorithm doesn't handle
+    let mut empty_pending = n == 0;
+
+    if n > 0 {
+        parts[1] = sum;
+    }
+
+    std::iter::from_fn(move || {
+        if empty_pending {
+            empty_pending = false;
+            return Some(Vec::new());
+        }
+
+        if n == 0 || k == 0 {
+            return None;
+        }
+        let mut x = parts[k - 1] + 1;
+        let mut y = parts[k] - 1;
+        k -= 1;
+
+        while x <= y {
+            parts[k] = x;
+            y -= x;
+            k += 1;
+        }
+        x += y;
+        parts[k] = x;
+        Some(parts[..=k].to_vec())
+    })
+}
+
 /// Verify whether a point set is a valid strong orthogonal array based on the metadata supplied in
 /// that struct. This method returns whether the given SOA is valid, based on the metadata. It will
 /// check that the SOA maintains the stratification guarantees based on the properties of the SOA.
 pub fn verify(soa: &SOA) -> bool {
+    verify_points(&soa.points, soa.strength, soa.base)
+}
+
+/// Verify whether a matrix is a valid strong orthogonal array with the supplied strength and base.
+///
+/// This is the same as `verify`, but it doesn't require wrapping the matrix in an `SOA` struct,
+/// which is convenient for arrays that were loaded from elsewhere.
+pub fn verify_points(points: &Array2<u32>, strength: u32, base: u32) -> bool {
     // The exponents for each strata. For example, [1, 1, 1] means s^1 x s^1 x s^1 strata
-    let strata_exp = sum_perms(soa.strength);
+    let strata_exp = integer_partitions_iter(strength);
 
     // In this loop, we test each combination of strata to ensure that the SOA can be
     // reduced down to some lower asymmetrical orthogonal array
     for curr_strata in strata_exp {
-        // this yields every possible permutation of the strata exponents
-        let strata_perms = curr_strata.iter().combinations(curr_strata.len());
+        // this yields every distinct permutation of the strata exponents. Repeated exponents
+        // yield the same permutation more than once, which only needs to be checked once.
+        let strata_perms = curr_strata.iter().permutations(curr_strata.len()).unique();
 
         // For each permutation of strata, we have to try each permutation relative to each axis
         // For example, for s^2 x s, we check to see if dim 0 is stratified with s^2, and
@@ -98,7 +245,7 @@ pub fn verify(soa: &SOA) -> bool {
                 // referencing the strata permutation vector and there's no benefit to
                 // getting a pointer to a pointer
                 .iter()
-                .map(|x| 0..soa.base.pow(**x))
+                .map(|x| 0..base.pow(**x))
                 .multi_cartesian_product();
 
             let mut combo_counter: HashMap<Vec<u32>, u32> =
@@ -112,16 +259,16 @@ pub fn verify(soa: &SOA) -> bool {
             // We don't care how many instances of each pair are present because He and Tang define
             // an OA to be one with an arbitrary index (aka as long as we have the same number of
             // each tuple we're good to go), or lambda >= 1
-            let column_combos = (0..soa.points.shape()[1]).combinations(strata_perm.len());
+            let column_combos = (0..points.shape()[1]).combinations(strata_perm.len());
 
             for col_combo in column_combos {
-                combo_counter = combo_counter.iter().map(|(k, _)| (k.clone(), 0)).collect();
+                combo_counter = combo_counter.keys().map(|k| (k.clone(), 0)).collect();
 
-                for row in soa.points.rows() {
+                for row in points.rows() {
                     let mut point = Vec::new();
 
                     for (strata_pow, col) in zip(strata_perm.iter(), col_combo.iter()) {
-                        point.push(row[[*col]] / soa.base.pow(soa.strength - **strata_pow));
+                        point.push(row[[*col]] / base.pow(strength - **strata_pow));
                     }
 
                     // if the row of the reduced OA is not in the hash map, then there is an error
@@ -146,11 +293,75 @@ pub fn verify(soa: &SOA) -> bool {
     true
 }
 
+/// Find the highest strength that a strong orthogonal array actually achieves.
+///
+/// An SOA of strength $t$ has $s^t$ levels, and dividing its entries by $s^{t - t'}$ yields an
+/// SOA of strength $t'$. This tries every strength from the stored `strength` down to 1, and
+/// returns the first one where the reduced array passes `verify`. This returns 0 if the array
+/// isn't an SOA of any strength.
+pub fn empirical_strength(soa: &SOA) -> u32 {
+    (1..=soa.strength)
+        .rev()
+        .find(|&strength| {
+            let divisor = soa.base.pow(soa.strength - strength);
+            verify_points(&soa.points.mapv(|x| x / divisor), strength, soa.base)
+        })
+        .unwrap_or(0)
+}
+
+/// Normalize a strong orthogonal array into a point set in the $[0, 1)^m$ domain.
+///
+/// Each entry is divided by the number of levels in the SOA, which is $s^t$. A random offset
+/// between 0 and `jitter` is added to each entry before it is scaled, so a jitter of 0 places
+/// each point at the lower corner of its strata. If `randomize` is set, the runs of each column
+/// are shuffled with a separate permutation. This only keeps the one-dimensional stratification
+/// of each column: shuffling the columns independently breaks the joint $s^a \times s^b$ strata
+/// of the SOA, so use `to_point_set` to keep them.
+pub fn normalize(
+    soa: &SOA,
+    jitter: f64,
+    randomize: bool,
+    rng: &mut impl Rng,
+) -> OarsResult<Array2<f64>> {
+    if !(0.0..=1.0).contains(&jitter) {
+        return Err(OarsError::InvalidParams(
+            "`jitter` must be between 0 and 1".to_owned(),
+        ));
+    }
+    let levels = soa.base.checked_pow(soa.strength).ok_or_else(|| {
+        OarsError::InvalidParams("The number of levels in the SOA overflows".to_owned())
+    })?;
+    let levels = f64::from(levels);
+    let (rows, cols) = soa.points.dim();
+    let mut point_set = Array2::<f64>::zeros((rows, cols));
+    let perms = column_permutations(rows, cols, randomize, rng);
+
+    for i in 0..rows {
+        for (j, perm) in perms.iter().enumerate() {
+            let level = f64::from(soa.points[[i, j]]);
+            let jittered_point = level + jitter * rng.gen::<f64>();
+            point_set[[perm[i], j]] = clamp_to_strata(jittered_point, level, levels);
+        }
+    }
+    Ok(point_set)
+}
+
+/// Convert a strong orthogonal array into a point set that keeps every one of its stratification
+/// guarantees.
+///
+/// Each point is placed uniformly at random within its cell of the finest $s^t$ grid. The runs are
+/// not shuffled, since shuffling the columns independently would break the stratification on the
+/// coarser grids.
+pub fn to_point_set(soa: &SOA, rng: &mut impl Rng) -> OarsResult<Array2<f64>> {
+    normalize(soa, 1.0, false, rng)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use ndarray::array;
     use rand::prelude::*;
+    use rand::rngs::mock::StepRng;
     use std::collections::HashSet;
 
     #[test]
@@ -189,6 +400,21 @@ mod tests {
         }
     }
 
+    /// The strength 3 SOA with base 2, 8 runs, and 3 factors from "A Characterization of Strong
+    /// Orthogonal Arrays of Strength 3" (He and Tang, 2014).
+    fn he_tang_soa() -> Array2<u32> {
+        array![
+            [0, 0, 0],
+            [2, 3, 6],
+            [3, 6, 2],
+            [1, 5, 4],
+            [6, 2, 3],
+            [4, 1, 5],
+            [5, 4, 1],
+            [7, 7, 7],
+        ]
+    }
+
     #[test]
     fn test_verify_valid_soa() {
         let ground_truth = array![
@@ -224,22 +450,10 @@ mod tests {
         };
         assert!(verify(&soa));
 
-        // Taken from "A Characterization of Strong Orthogonal Arrays of Strength 3" (He and Tang,
-        // 2014).
-        let ground_truth = array![
-            [0, 0, 0],
-            [2, 3, 6],
-            [3, 6, 2],
-            [1, 5, 4],
-            [6, 2, 3],
-            [4, 1, 5],
-            [5, 4, 1],
-            [7, 7, 7],
-        ];
         let soa = SOA {
             strength: 3,
             base: 2,
-            points: ground_truth,
+            points: he_tang_soa(),
         };
         assert!(verify(&soa));
     }
@@ -314,4 +528,247 @@ mod tests {
         };
         assert!(!verify(&soa));
     }
+
+    #[test]
+    fn test_integer_partitions_iter_matches_sum_perms() {
+        for sum in 0..=7 {
+            let lazy: Vec<Vec<u32>> = integer_partitions_iter(sum).collect();
+            let lazy_set: HashSet<Vec<u32>> = lazy.iter().cloned().collect();
+            let eager_set: HashSet<Vec<u32>> = sum_perms(sum).into_iter().collect();
+            assert!(lazy.len() == lazy_set.len());
+            assert!(lazy_set == eager_set);
+        }
+        assert!(integer_partitions_iter(7).count() == 15);
+    }
+
+    #[test]
+    fn test_normalize() {
+        let soa = SOA {
+            strength: 3,
+            base: 2,
+            points: he_tang_soa(),
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for &jitter in &[0.0, 0.5, 1.0] {
+            let points = normalize(&soa, jitter, true, &mut rng).unwrap();
+            assert!(points.dim() == (8, 3));
+            assert!(points.iter().all(|x| (0.0..1.0).contains(x)));
+        }
+
+        // Without jitter or shuffling, every point should sit at the bottom corner of its strata
+        let points = normalize(&soa, 0.0, false, &mut rng).unwrap();
+        assert!(points == soa.points.mapv(|x| f64::from(x) / 8.0));
+        assert!(normalize(&soa, 1.5, false, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_normalize_stays_in_strata() {
+        let soa = SOA {
+            strength: 3,
+            base: 2,
+            points: array![[1, 6, 7], [7, 1, 6]],
+        };
+        // This generator yields the largest possible offset, which rounds up to the next strata
+        // when it is added to a nonzero entry
+        let mut rng = StepRng::new(u64::MAX, 0);
+        let points = normalize(&soa, 1.0, false, &mut rng).unwrap();
+
+        for (&x, &level) in points.iter().zip(soa.points.iter()) {
+            assert!((x * 8.0).floor() == f64::from(level));
+            assert!(x < 1.0);
+        }
+    }
+
+    #[test]
+    fn test_to_point_set() {
+        let soa = SOA {
+            strength: 3,
+            base: 2,
+            points: he_tang_soa(),
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        let points = to_point_set(&soa, &mut rng).unwrap();
+
+        // Every pair of columns should be stratified on the s^2 x s grid, with one point per cell
+        for (a, b) in (0..3).tuple_combinations() {
+            for (a, b) in [(a, b), (b, a)] {
+                let cells: HashSet<(u32, u32)> = points
+                    .rows()
+                    .into_iter()
+                    .map(|row| ((row[a] * 4.0) as u32, (row[b] * 2.0) as u32))
+                    .collect();
+                assert!(cells.len() == 8);
+            }
+        }
+
+        // Every column should be stratified on the finest s^3 grid
+        for col in points.columns() {
+            let cells: HashSet<u32> = col.iter().map(|x| (x * 8.0) as u32).collect();
+            assert!(cells.len() == 8);
+        }
+    }
+
+    #[test]
+    fn test_empirical_strength() {
+        let mut soa = SOA {
+            strength: 3,
+            base: 2,
+            points: he_tang_soa(),
+        };
+        assert!(empirical_strength(&soa) == 3);
+
+        // Swapping two entries that only differ in their middle digit keeps the coarser strata
+        // intact
+        soa.points[[1, 2]] = 4;
+        soa.points[[3, 2]] = 6;
+        assert!(empirical_strength(&soa) == 2);
+
+        soa.points.column_mut(0).fill(0);
+        assert!(empirical_strength(&soa) == 0);
+    }
+
+    #[test]
+    fn test_integer_partitions_order() {
+        let expected = vec![
+            vec![1, 1, 1, 1],
+            vec![1, 1, 2],
+            vec![1, 3],
+            vec![2, 2],
+            vec![4],
+        ];
+        assert!(integer_partitions_iter(4).collect::<Vec<_>>() == expected);
+        assert!(sum_perms(4) == expected);
+    }
+
+    #[test]
+    fn test_verify_points() {
+        assert!(verify_points(&he_tang_soa(), 3, 2));
+        assert!(!verify_points(&he_tang_soa().mapv(|x| x % 4), 3, 2));
+    }
+
+    #[test]
+    fn test_num_levels() {
+        let soa = SOA {
+            strength: 3,
+            base: 2,
+            points: he_tang_soa(),
+        };
+        assert!(soa.num_levels() == 8);
+        assert!(soa.as_oa_params() == (8, 3));
+        assert!(soa.points.iter().all(|&x| x < soa.num_levels()));
+    }
+
+    /// A strength 4 SOA with base 2, 16 runs, and 3 factors. Every digit of an entry is a linear
+    /// form over GF(2) of the run index, and the forms were chosen so that every strata
+    /// combination up to $s^4$ is balanced.
+    fn strength_4_soa() -> Array2<u32> {
+        array![
+            [0, 0, 0],
+            [6, 3, 15],
+            [10, 2, 4],
+            [12, 1, 11],
+            [8, 7, 12],
+            [14, 4, 3],
+            [2, 5, 8],
+            [4, 6, 7],
+            [9, 9, 1],
+            [15, 10, 14],
+            [3, 11, 5],
+            [5, 8, 10],
+            [1, 14, 13],
+            [7, 13, 2],
+            [11, 12, 9],
+            [13, 15, 6],
+        ]
+    }
+
+    #[test]
+    fn test_verify_strength_4_soa() {
+        let soa = SOA {
+            strength: 4,
+            base: 2,
+            points: strength_4_soa(),
+        };
+        assert!(verify(&soa));
+        assert!(empirical_strength(&soa) == 4);
+
+        // Reducing the array yields SOAs of every lower strength
+        for strength in 1..4 {
+            let divisor = 2u32.pow(4 - strength);
+            assert!(verify_points(
+                &soa.points.mapv(|x| x / divisor),
+                strength,
+                2
+            ));
+        }
+
+        let mut corrupted = strength_4_soa();
+        corrupted.swap([0, 2], [1, 2]);
+        assert!(!verify_points(&corrupted, 4, 2));
+    }
+
+    #[test]
+    fn test_verify_checks_every_strata_order() {
+        // This array is stratified on every `s^a x s^b (x s^c)` grid where the exponents are
+        // assigned to the columns in ascending order, but not on the `s^3 x s` grid for the first
+        // two columns, so it is not an SOA.
+        let points = array![
+            [0, 0, 0],
+            [4, 14, 3],
+            [13, 1, 4],
+            [9, 15, 7],
+            [14, 11, 8],
+            [10, 5, 11],
+            [3, 10, 12],
+            [7, 4, 15],
+            [15, 6, 2],
+            [11, 8, 1],
+            [2, 7, 6],
+            [6, 9, 5],
+            [1, 13, 10],
+            [5, 3, 9],
+            [12, 12, 14],
+            [8, 2, 13],
+        ];
+        assert!(!verify_points(&points, 4, 2));
+    }
+
+    #[test]
+    fn test_soa_from_matrix() {
+        use crate::constructors::Bose;
+        use crate::oa::OAConstructor;
+
+        let oa = Bose {
+            prime_base: 3,
+            dimensions: 4,
+        }
+        .gen()
+        .unwrap();
+
+        // Column j of the SOA is `3 * d_j + d_{j + 1}`, where d_j is column j of the OA. The
+        // leading digits are columns of an OA of strength 2, and every column is stratified on
+        // the s^2 grid, since every pair of columns of the OA is balanced.
+        let matrix = array![[3, 0, 0], [1, 3, 0], [0, 1, 3], [0, 0, 1]];
+        let soa = SOAFromMatrix {
+            oa: &oa,
+            matrix,
+            strength: 2,
+        }
+        .gen()
+        .unwrap();
+        let expected = Array2::from_shape_fn((9, 3), |(i, j)| {
+            3 * oa.points[[i, j]] + oa.points[[i, j + 1]]
+        });
+        assert!(soa.points == expected);
+        assert!(soa.base == 3);
+        assert!(verify(&soa));
+
+        let soa = SOAFromMatrix {
+            oa: &oa,
+            matrix: array![[3, 0], [1, 3]],
+            strength: 2,
+        };
+        assert!(soa.gen().is_err());
+    }
 }
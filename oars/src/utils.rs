@@ -1,6 +1,8 @@
 //! Misc utilities and convenience functions for the library
 
 use num::{self, NumCast};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use std::vec::Vec;
 use thiserror::Error;
 
@@ -38,6 +40,32 @@ pub trait Float: NumCast + num::Float + Copy + std::marker::Send + Sync {}
 #[cfg(feature = "parallel")]
 impl<T> Float for T where T: NumCast + num::Float + Copy + std::marker::Send + Sync {}
 
+/// Configuration for the random number generators used by randomized operations.
+///
+/// Functions that take an `RngConfig` use a deterministic generator seeded with `seed` if it is
+/// set, which makes their output reproducible. Otherwise, the generator is seeded from system
+/// entropy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RngConfig {
+    /// The seed for the random number generator, if there is one
+    pub seed: Option<u64>,
+}
+
+impl RngConfig {
+    /// Create a configuration that seeds every generator with `seed`
+    pub fn seeded(seed: u64) -> Self {
+        Self { seed: Some(seed) }
+    }
+
+    /// Create a new random number generator as described by the configuration
+    pub fn rng(&self) -> StdRng {
+        match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        }
+    }
+}
+
 /// Convert a number to an arbitrary base with a fixed number of digits
 ///
 /// Given some number, convert the number to some base with a specified number of digits. This
@@ -50,9 +78,8 @@ where
     let mut new_base = vec![T::from(0).unwrap(); degree.to_usize().unwrap()];
     let mut new_num = num;
 
-    for i in 0..degree.to_usize().unwrap() {
-        let i = i as usize;
-        new_base[i] = new_num % base;
+    for digit in new_base.iter_mut() {
+        *digit = new_num % base;
         new_num = new_num / base;
     }
     new_base
@@ -75,15 +102,59 @@ where
     result
 }
 
+/// Convert an integer to a `usize`, returning an error instead of panicking if the value does not
+/// fit.
+pub fn as_usize<T: Integer>(x: T) -> OarsResult<usize> {
+    x.to_usize().ok_or_else(|| {
+        OarsError::InvalidParams("A value does not fit in the range of a `usize`".to_owned())
+    })
+}
+
+/// Convert a `usize` to an integer type, returning an error instead of panicking if the value
+/// does not fit in the target type.
+pub fn from_usize_checked<T: Integer>(x: usize) -> OarsResult<T> {
+    T::from(x).ok_or_else(|| {
+        OarsError::InvalidParams(format!(
+            "{} does not fit in the range of the target integer type",
+            x
+        ))
+    })
+}
+
+/// Format an integer in base 10.
+///
+/// Every primitive integer fits in either an `i128` or a `u128`, so this only falls back to `?`
+/// for integer types that are wider than 128 bits.
+pub(crate) fn format_integer<T: Integer>(x: T) -> String {
+    x.to_i128()
+        .map(|x| x.to_string())
+        .or_else(|| x.to_u128().map(|x| x.to_string()))
+        .unwrap_or_else(|| "?".to_owned())
+}
+
+/// Raise `base` to the power of `exp`, returning `None` instead of wrapping if the result does not
+/// fit in the integer type. This also returns `None` if `base` is negative.
+pub fn checked_pow<T: Integer>(base: T, exp: usize) -> Option<T> {
+    let base = base.to_u128()?;
+    let mut acc: u128 = 1;
+
+    for _ in 0..exp {
+        acc = acc.checked_mul(base)?;
+    }
+    T::from(acc)
+}
+
 /// The general categories of errors for `OarsError`
-#[derive(Debug)]
+///
+/// Each category has a stable numeric code, which is its discriminant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ErrorKind {
     /// Invalid parameters were supplied to the constructor
-    InvalidParams,
+    InvalidParams = 1,
 
     /// There was a runtime error that prevented the orthogonal array from being properly
     /// constructed
-    RuntimeError,
+    RuntimeError = 2,
 }
 
 /// An error indicating that there was some error constructing the orthogonal array.
@@ -92,11 +163,110 @@ pub enum OarsError {
     #[error("Invalid params supplied to the constructor: {0}")]
     InvalidParams(String),
 
-    #[error("There was an error pertaining to the shape of a matrix")]
+    #[error("There was a runtime error: {0}")]
+    RuntimeError(String),
+
+    #[error(
+        "{}There was an error pertaining to the shape of a matrix: {source}",
+        context_prefix(.context)
+    )]
     ShapeError {
-        #[from]
         source: ndarray::ShapeError,
+
+        /// The context that was added with `OarsError::context`, if any
+        context: Option<String>,
     },
+
+    #[error("{}There was an I/O error: {source}", context_prefix(.context))]
+    IoError {
+        source: std::io::Error,
+
+        /// The context that was added with `OarsError::context`, if any
+        context: Option<String>,
+    },
+}
+
+impl From<ndarray::ShapeError> for OarsError {
+    fn from(source: ndarray::ShapeError) -> Self {
+        OarsError::ShapeError {
+            source,
+            context: None,
+        }
+    }
+}
+
+impl From<std::io::Error> for OarsError {
+    fn from(source: std::io::Error) -> Self {
+        OarsError::IoError {
+            source,
+            context: None,
+        }
+    }
+}
+
+/// Format the context of an error that wraps an error from another library, so it can be prepended
+/// to the description of the error
+fn context_prefix(context: &Option<String>) -> String {
+    context
+        .as_ref()
+        .map_or_else(String::new, |ctx| format!("{}: ", ctx))
+}
+
+/// Prepend some context to the existing context of an error
+fn add_context(ctx: &str, context: Option<String>) -> Option<String> {
+    Some(match context {
+        Some(inner) => format!("{}: {}", ctx, inner),
+        None => ctx.to_owned(),
+    })
+}
+
+impl OarsError {
+    /// Get the general category of the error
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            OarsError::InvalidParams(_) => ErrorKind::InvalidParams,
+            OarsError::RuntimeError(_)
+            | OarsError::ShapeError { .. }
+            | OarsError::IoError { .. } => ErrorKind::RuntimeError,
+        }
+    }
+
+    /// Get a stable numeric code for the error, which is the code of its `ErrorKind`.
+    ///
+    /// This is meant for FFI layers that need to translate errors without parsing the error
+    /// message.
+    pub fn code(&self) -> u32 {
+        self.kind() as u32
+    }
+
+    /// Prepend some context to the description of the error, such as the stage of a pipeline that
+    /// failed.
+    ///
+    /// The kind of the error is preserved. Errors that wrap an error from another library keep the
+    /// original error as their source, and store the context alongside it.
+    ///
+    /// ```
+    /// use oars::OarsError;
+    ///
+    /// let e = OarsError::InvalidParams("Base is not prime".to_owned())
+    ///     .context("while building SOA base");
+    /// assert!(e.to_string().contains("while building SOA base"));
+    /// assert!(e.to_string().contains("Base is not prime"));
+    /// ```
+    pub fn context(self, ctx: &str) -> OarsError {
+        match self {
+            OarsError::InvalidParams(msg) => OarsError::InvalidParams(format!("{}: {}", ctx, msg)),
+            OarsError::RuntimeError(msg) => OarsError::RuntimeError(format!("{}: {}", ctx, msg)),
+            OarsError::ShapeError { source, context } => OarsError::ShapeError {
+                source,
+                context: add_context(ctx, context),
+            },
+            OarsError::IoError { source, context } => OarsError::IoError {
+                source,
+                context: add_context(ctx, context),
+            },
+        }
+    }
 }
 
 /// A generic type for anything that can return an `OarsError`.
@@ -157,4 +327,57 @@ mod tests {
         let result = poly_eval(&coeffs, base);
         assert!(result == 9);
     }
+
+    #[test]
+    fn test_checked_casts() {
+        assert!(as_usize(5_u32).unwrap() == 5);
+        assert!(as_usize(-1_i32).is_err());
+        assert!(from_usize_checked::<u8>(255).unwrap() == 255);
+        assert!(from_usize_checked::<u8>(256).is_err());
+        assert!(from_usize_checked::<i16>(usize::MAX).is_err());
+    }
+
+    #[test]
+    fn test_error_codes() {
+        assert!(ErrorKind::InvalidParams as u32 == 1);
+        assert!(ErrorKind::RuntimeError as u32 == 2);
+
+        assert!(OarsError::InvalidParams("".to_owned()).code() == 1);
+        assert!(OarsError::RuntimeError("".to_owned()).code() == 2);
+
+        let shape_err = ndarray::Array2::<u32>::from_shape_vec((2, 2), vec![0]).unwrap_err();
+        assert!(OarsError::from(shape_err).code() == 2);
+
+        let io_err = std::io::Error::other("");
+        assert!(OarsError::from(io_err).code() == 2);
+    }
+
+    #[test]
+    fn test_checked_pow() {
+        assert!(checked_pow(3u32, 4) == Some(81));
+        assert!(checked_pow(3u32, 0) == Some(1));
+        assert!(checked_pow(2u8, 8).is_none());
+        assert!(checked_pow(2u8, 7) == Some(128));
+        assert!(checked_pow(-2i32, 2).is_none());
+    }
+
+    #[test]
+    fn test_error_context() {
+        let e = OarsError::InvalidParams("Base is not prime".to_owned())
+            .context("while building SOA base");
+        let msg = e.to_string();
+        assert!(msg.contains("while building SOA base"));
+        assert!(msg.contains("Base is not prime"));
+        assert!(e.kind() == ErrorKind::InvalidParams);
+
+        let e = OarsError::from(std::io::Error::other("disk on fire"))
+            .context("while parsing")
+            .context("while reading");
+        let msg = e.to_string();
+        assert!(msg.starts_with("while reading: while parsing: "));
+        assert!(msg.contains("disk on fire"));
+        assert!(matches!(e, OarsError::IoError { .. }));
+        assert!(std::error::Error::source(&e).unwrap().to_string() == "disk on fire");
+        assert!(e.kind() == ErrorKind::RuntimeError);
+    }
 }
@@ -72,3 +72,18 @@ fn test_bose_normalize() {
     assert!(normalize(&oa, 0.0, true).is_ok());
     assert!(normalize(&oa, 1.0, true).is_ok());
 }
+
+#[test]
+#[cfg(feature = "parallel")]
+fn test_bose_par_with_threads() {
+    let bose = Bose {
+        prime_base: 5,
+        dimensions: 4,
+    };
+    let oa = bose.gen().unwrap();
+    let oa_par = bose.gen_par_with_threads(1).unwrap();
+    assert!(oa.points == oa_par.points);
+
+    let oa_par = bose.gen_par_with_threads(4).unwrap();
+    assert!(oa.points == oa_par.points);
+}
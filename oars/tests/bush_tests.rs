@@ -96,3 +96,52 @@ fn test_bush_normalize() {
     assert!(normalize(&oa, 0.0, true).is_ok());
     assert!(normalize(&oa, 1.0, true).is_ok());
 }
+
+#[test]
+#[cfg(feature = "parallel")]
+fn test_bush_par_with_threads() {
+    let bush = Bush {
+        prime_base: 5,
+        dimensions: 4,
+        strength: 3,
+    };
+    let oa = bush.gen().unwrap();
+    let oa_par = bush.gen_par_with_threads(1).unwrap();
+    assert!(oa.points == oa_par.points);
+
+    let oa_par = bush.gen_par_with_threads(4).unwrap();
+    assert!(oa.points == oa_par.points);
+}
+
+#[test]
+fn test_bush_last_column_verify() {
+    let bush = Bush {
+        prime_base: 5,
+        dimensions: 6,
+        strength: 3,
+    };
+    let oa = bush.gen().unwrap();
+    assert!(verify(&oa).unwrap());
+
+    let bush = Bush {
+        prime_base: 3,
+        dimensions: 4,
+        strength: 2,
+    };
+    let oa = bush.gen().unwrap();
+    assert!(verify(&oa).unwrap());
+}
+
+#[test]
+#[cfg(feature = "parallel")]
+fn test_bush_par_last_column_verify() {
+    let bush = Bush {
+        prime_base: 5,
+        dimensions: 6,
+        strength: 3,
+    };
+    let oa = bush.gen().unwrap();
+    let oa_par = bush.gen_par().unwrap();
+    assert!(oa.points == oa_par.points);
+    assert!(verify(&oa_par).unwrap());
+}